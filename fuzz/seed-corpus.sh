@@ -0,0 +1,14 @@
+#!/bin/sh
+# Seeds each day's corpus with its example inputs, run `cargo fuzz run dayXX` afterwards
+set -e
+cd "$(dirname "$0")"
+
+for day in ../src/day*/; do
+    name=$(basename "$day")
+    mkdir -p "corpus/$name"
+    for example in "$day"example*.txt; do
+        if [ -e "$example" ]; then
+            cp "$example" "corpus/$name/"
+        fi
+    done
+done
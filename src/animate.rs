@@ -0,0 +1,175 @@
+use std::{
+    fmt::Write as _,
+    io::{self, IsTerminal, Write},
+    num::NonZeroUsize,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use aoc_utils::grid::Grid;
+use colored::{Color, Colorize};
+
+use crate::render::{Palette, Rgb};
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    /// Frames drawn per second during interactive playback
+    pub fps: f64,
+    /// Frames before this one are skipped without being built
+    pub from_frame: usize,
+    /// Only every `every`-th frame is drawn
+    pub every: NonZeroUsize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            fps: 30.0,
+            from_frame: 0,
+            every: NonZeroUsize::MIN,
+        }
+    }
+}
+
+/// Enables animation playback for every [`Animation`] created afterwards.
+pub fn enable(settings: Settings) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        settings.fps.is_finite() && settings.fps > 0.0,
+        "Animation speed must be a positive number of frames per second"
+    );
+
+    SETTINGS
+        .set(settings)
+        .map_err(|_| anyhow::anyhow!("Animation is already configured"))
+}
+
+pub fn is_enabled() -> bool {
+    SETTINGS.get().is_some()
+}
+
+/// A sequence of frames drawn to the terminal.
+///
+/// On a TTY every frame is redrawn in place at the configured speed, otherwise frames are printed
+/// one after the other without colours so they can be read back from logs.
+#[derive(Debug)]
+pub struct Animation {
+    name: &'static str,
+    settings: Option<Settings>,
+    interactive: bool,
+    index: usize,
+    drawn_lines: usize,
+    next_frame_at: Option<Instant>,
+}
+
+impl Animation {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            settings: SETTINGS.get().copied(),
+            interactive: io::stdout().is_terminal(),
+            index: 0,
+            drawn_lines: 0,
+            next_frame_at: None,
+        }
+    }
+
+    /// An animation that never draws, for code shared between animated and silent callers.
+    pub fn disabled() -> Self {
+        Self {
+            settings: None,
+            ..Self::new("")
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.is_some()
+    }
+
+    /// Records a frame. `grid` is only called if the frame is actually drawn.
+    pub fn frame(&mut self, grid: impl FnOnce() -> Grid<char>, palette: impl Palette<char>) {
+        let index = self.index;
+        self.index += 1;
+
+        let Some(settings) = self.settings else {
+            return;
+        };
+
+        if index < settings.from_frame || (index - settings.from_frame) % settings.every != 0 {
+            return;
+        }
+
+        let grid = grid();
+        let result = if self.interactive {
+            self.draw_in_place(index, &grid, &palette, settings.fps)
+        } else {
+            self.draw_plain(index, &grid)
+        };
+
+        if let Err(e) = result {
+            tracing::warn!(%e, "Unable to draw animation frame, stopping playback");
+            self.settings = None;
+        }
+    }
+
+    fn draw_in_place(
+        &mut self,
+        index: usize,
+        grid: &Grid<char>,
+        palette: &impl Palette<char>,
+        fps: f64,
+    ) -> io::Result<()> {
+        let mut buffer = String::new();
+        if self.drawn_lines > 0 {
+            // Move the cursor back to the first line of the previous frame
+            let _ = write!(buffer, "\x1b[{}F", self.drawn_lines);
+        }
+
+        let _ = writeln!(buffer, "\x1b[2K{} frame {index}", self.name.bold());
+        for row in grid.rows() {
+            for tile in row.iter() {
+                let _ = write!(
+                    buffer,
+                    "{}",
+                    tile.to_string().color(color(palette.color(tile)))
+                );
+            }
+
+            buffer.push('\n');
+        }
+
+        self.drawn_lines = grid.height() as usize + 1;
+
+        if let Some(next_frame_at) = self.next_frame_at {
+            std::thread::sleep(next_frame_at.saturating_duration_since(Instant::now()));
+        }
+
+        self.next_frame_at = Some(Instant::now() + Duration::from_secs_f64(1.0 / fps));
+
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(buffer.as_bytes())?;
+        stdout.flush()
+    }
+
+    fn draw_plain(&mut self, index: usize, grid: &Grid<char>) -> io::Result<()> {
+        let mut buffer = format!("{} frame {index}\n", self.name);
+        for row in grid.rows() {
+            buffer.extend(row.iter());
+            buffer.push('\n');
+        }
+
+        buffer.push('\n');
+        io::stdout().lock().write_all(buffer.as_bytes())
+    }
+}
+
+fn color(rgb: Rgb) -> Color {
+    if rgb == Rgb::BLACK {
+        // Pure black would be invisible on most terminal backgrounds
+        Color::BrightBlack
+    } else {
+        let Rgb(r, g, b) = rgb;
+        Color::TrueColor { r, g, b }
+    }
+}
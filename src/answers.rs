@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use anyhow::Context;
+use aoc_utils::{hashbrown::HashMap, AocError};
+
+/// Expected answers, one `DAY-PART: ANSWER` line each.
+///
+/// Lines may keep the `Problem ` prefix, so saved runner output can be used as is.
+#[derive(Debug, Default, Clone)]
+pub struct Answers {
+    answers: HashMap<(i32, usize), String>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+
+        Self::parse(&contents).with_context(|| format!("Invalid answers {}", path.display()))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, AocError> {
+        let mut answers = HashMap::new();
+        for line in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            let line = line.strip_prefix("Problem ").unwrap_or(line);
+            let (id, answer) = line.split_once(':').ok_or(AocError::InvalidInput)?;
+            let (day, part) = id.split_once('-').ok_or(AocError::InvalidInput)?;
+            let day = day.parse().map_err(|_| AocError::InvalidInput)?;
+            let part = part.parse().map_err(|_| AocError::InvalidInput)?;
+
+            answers.insert((day, part), answer.trim().to_owned());
+        }
+
+        Ok(Self { answers })
+    }
+
+    /// Expected answer for `part` (starting at 1) of `day`.
+    pub fn get(&self, day: i32, part: usize) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    /// Whether the runner's `output` is the `expected` answer, ignoring anything the harness
+    /// appends after it (e.g. the timing).
+    pub fn matches(expected: &str, output: &str) -> bool {
+        match output.strip_prefix(expected) {
+            Some(rest) => rest.is_empty() || rest.starts_with(' '),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse("1-1: 11\nProblem 1-2: 31\n\n24-2: a,b,c\n").unwrap();
+
+        assert_eq!(answers.get(1, 1), Some("11"));
+        assert_eq!(answers.get(1, 2), Some("31"));
+        assert_eq!(answers.get(24, 2), Some("a,b,c"));
+        assert_eq!(answers.get(2, 1), None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Answers::parse("1: 11").is_err());
+        assert!(Answers::parse("one-1: 11").is_err());
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(Answers::matches("11", "11"));
+        assert!(Answers::matches("11", "11 (1.2ms)"));
+        assert!(!Answers::matches("11", "110"));
+        assert!(!Answers::matches("11", "12"));
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    panic::{AssertUnwindSafe, Location},
+    sync::Once,
+};
+
+static INSTALL: Once = Once::new();
+
+thread_local! {
+    /// Number of boundaries running on this thread, while which its panics are recorded instead
+    /// of printed.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// A panic caught by [`catch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panic {
+    pub message: String,
+    pub location: Option<String>,
+}
+
+impl std::fmt::Display for Panic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PANIC: {}", self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Panic {}
+
+/// Runs `f`, turning a panic into an error instead of unwinding further.
+///
+/// Panics on other threads spawned by `f` (e.g. by rayon) are caught as well, as long as they
+/// propagate back to `f`, but they are printed and their location isn't recorded.
+pub fn catch<R>(f: impl FnOnce() -> R) -> Result<R, Panic> {
+    INSTALL.call_once(install_hook);
+
+    let depth = DEPTH.get();
+    if depth == 0 {
+        LOCATION.set(None);
+    }
+
+    DEPTH.set(depth + 1);
+    let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+    DEPTH.set(depth);
+
+    let location = if depth == 0 {
+        LOCATION.take()
+    } else {
+        LOCATION.with_borrow(Clone::clone)
+    };
+
+    result.map_err(|payload| Panic {
+        message: payload_message(payload.as_ref()),
+        location,
+    })
+}
+
+fn install_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Threads outside boundaries, or being torn down, panic as usual
+        if DEPTH.try_with(Cell::get).unwrap_or(0) == 0 {
+            default_hook(info);
+            return;
+        }
+
+        // Keep the first location, later panics are usually caused by the first one
+        LOCATION.with_borrow_mut(|location| {
+            if location.is_none() {
+                *location = info.location().map(Location::to_string);
+            }
+        });
+    }));
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else if payload.is::<crate::cancel::Cancelled>() {
+        "Cancelled".to_owned()
+    } else {
+        "Box<dyn Any>".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_ok() {
+        assert_eq!(catch(|| 42), Ok(42));
+    }
+
+    #[test]
+    fn test_catch_panic() {
+        let result = catch(|| {
+            let values: Vec<u32> = Vec::new();
+            values[3]
+        });
+
+        let panic = result.unwrap_err();
+        assert!(panic.message.contains("index out of bounds"));
+        assert!(panic.location.unwrap().contains("boundary.rs"));
+    }
+
+    #[test]
+    fn test_catch_ignores_other_threads() {
+        let line = std::sync::Mutex::new(0);
+        let result = catch(|| {
+            // A panic on an unrelated thread is neither silenced nor blamed on this boundary
+            let other = std::thread::spawn(|| panic!("Other thread"));
+            assert!(other.join().is_err());
+
+            *line.lock().unwrap() = line!() + 1;
+            panic!("This thread");
+        });
+
+        let panic = result.unwrap_err();
+        assert_eq!(panic.message, "This thread");
+        let line = line.into_inner().unwrap();
+        assert!(panic
+            .location
+            .unwrap()
+            .contains(&format!("boundary.rs:{line}:")));
+    }
+
+    #[test]
+    fn test_catch_formatted_panic() {
+        let result = catch(|| panic!("Invalid value {}", 7));
+        assert_eq!(result.unwrap_err().message, "Invalid value 7");
+    }
+}
@@ -0,0 +1,168 @@
+use std::{cell::Cell, collections::BTreeMap, sync::Mutex};
+
+use aoc_utils::cache::Cache;
+use serde::Serialize;
+
+/// Usage of the caches dropped since the last [`take_stats`], by name.
+static STATS: Mutex<BTreeMap<&'static str, CacheStats>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub name: &'static str,
+    /// Caches merged into these statistics
+    pub instances: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub inserts: u64,
+    /// Entries inserted through the wrappers, summed over the instances
+    pub size: u64,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+
+    fn merge(&mut self, other: &CacheStats) {
+        self.instances += other.instances;
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.inserts += other.inserts;
+        self.size += other.size;
+    }
+}
+
+/// A [`Cache`] that counts its hits, misses and inserts, and adds them to the statistics under
+/// its name when dropped.
+#[derive(Debug)]
+pub struct Instrumented<C> {
+    name: &'static str,
+    cache: C,
+    hits: Cell<u64>,
+    misses: Cell<u64>,
+    inserts: u64,
+    size: u64,
+}
+
+impl<C> Instrumented<C> {
+    pub fn new(name: &'static str, cache: C) -> Self {
+        Self {
+            name,
+            cache,
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+            inserts: 0,
+            size: 0,
+        }
+    }
+
+    /// The wrapped cache, to read without counting.
+    pub fn inner(&self) -> &C {
+        &self.cache
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            name: self.name,
+            instances: 1,
+            hits: self.hits.get(),
+            misses: self.misses.get(),
+            inserts: self.inserts,
+            size: self.size,
+        }
+    }
+}
+
+impl<K, V, C> Cache<K, V> for Instrumented<C>
+where
+    C: Cache<K, V>,
+{
+    fn get(&self, key: &K) -> Option<&V> {
+        let value = self.cache.get(key);
+        let counter = if value.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.set(counter.get() + 1);
+
+        value
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        if self.cache.get(&key).is_none() {
+            self.size += 1;
+        }
+
+        self.inserts += 1;
+        self.cache.insert(key, value);
+    }
+}
+
+impl<C> Drop for Instrumented<C> {
+    fn drop(&mut self) {
+        let stats = self.stats();
+        let mut all = STATS.lock().unwrap_or_else(|e| e.into_inner());
+        all.entry(self.name)
+            .or_insert_with(|| CacheStats {
+                name: self.name,
+                ..Default::default()
+            })
+            .merge(&stats);
+    }
+}
+
+/// Returns and clears the usage of the caches dropped so far.
+///
+/// The statistics are shared by every thread, since parts fill their caches from rayon's, so
+/// they include parts still running after timing out.
+pub fn take_stats() -> Vec<CacheStats> {
+    let mut all = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    std::mem::take(&mut *all).into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::hashbrown::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_instrumented() {
+        let mut cache = Instrumented::new("test-instrumented", HashMap::new());
+        assert_eq!(cache.get(&1), None);
+        cache.insert(1, "one");
+        cache.insert(1, "uno");
+        cache.insert(2, "two");
+        assert_eq!(cache.get(&1), Some(&"uno"));
+        assert_eq!(cache.get(&2), Some(&"two"));
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+        assert_eq!((stats.inserts, stats.size), (3, 2));
+        assert!((stats.hit_rate() - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut total = CacheStats::default();
+        for size in 1..=3 {
+            let mut cache = Instrumented::new("test", vec![None; size]);
+            for key in 0..size {
+                cache.insert(key, key);
+                cache.get(&key);
+            }
+
+            total.merge(&cache.stats());
+        }
+
+        assert_eq!(total.instances, 3);
+        assert_eq!((total.hits, total.misses), (6, 0));
+        assert_eq!((total.inserts, total.size), (6, 6));
+    }
+}
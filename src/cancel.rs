@@ -0,0 +1,154 @@
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+thread_local! {
+    /// Token of the part running on this thread, if it can be cancelled.
+    static CURRENT: RefCell<Option<Token>> = const { RefCell::new(None) };
+}
+
+/// Payload of the unwinding started by [`check`] once a part is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+/// Asks a part to stop, from any thread.
+///
+/// Cancellation is cooperative: the part only stops when it next calls [`check`] (as searches and
+/// progress counters do), by unwinding with a [`Cancelled`] payload.
+#[derive(Debug, Default, Clone)]
+pub struct Token(Arc<AtomicBool>);
+
+impl Token {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Unwinds out of the part if it was cancelled.
+    pub fn check(&self) {
+        if self.is_cancelled() {
+            // Unlike a panic, this doesn't run the panic hook
+            std::panic::resume_unwind(Box::new(Cancelled));
+        }
+    }
+}
+
+/// Checks for cancellation once every [`Periodic::INTERVAL`] steps of a hot loop.
+///
+/// The token is looked up once, so each step only costs a counter increment.
+#[derive(Debug)]
+pub struct Periodic {
+    token: Option<Token>,
+    steps: u32,
+}
+
+impl Periodic {
+    pub const INTERVAL: u32 = 1024;
+
+    /// Watches the token of the part running on this thread, if any.
+    pub fn new() -> Self {
+        Self {
+            token: current(),
+            steps: 0,
+        }
+    }
+
+    /// Unwinds out of the part if it was cancelled, checking every [`Self::INTERVAL`] calls.
+    pub fn check(&mut self) {
+        self.steps += 1;
+        if self.steps == Self::INTERVAL {
+            self.steps = 0;
+            if let Some(token) = &self.token {
+                token.check();
+            }
+        }
+    }
+}
+
+impl Default for Periodic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs `f` on this thread, letting it be cancelled through `token`.
+pub fn with_token<R>(token: Token, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Token>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let _ = CURRENT.try_with(|current| current.replace(self.0.take()));
+        }
+    }
+
+    let _restore = Restore(CURRENT.replace(Some(token)));
+    f()
+}
+
+/// Token of the part running on this thread, for work it hands to other threads.
+pub fn current() -> Option<Token> {
+    CURRENT.with_borrow(Clone::clone)
+}
+
+/// Unwinds out of the part running on this thread if it was cancelled.
+///
+/// Long loops call this every so often, it does nothing outside [`with_token`].
+pub fn check() {
+    CURRENT.with_borrow(|token| {
+        if let Some(token) = token {
+            token.check();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        // Nothing to cancel outside a token
+        check();
+
+        let token = Token::new();
+        let result = crate::boundary::catch(|| {
+            with_token(token.clone(), || {
+                check();
+                token.cancel();
+                check();
+                unreachable!("The part should have been cancelled");
+            })
+        });
+
+        assert_eq!(result.unwrap_err().message, "Cancelled");
+        assert!(current().is_none());
+    }
+
+    #[test]
+    fn test_periodic() {
+        let token = Token::new();
+        let steps = crate::boundary::catch(|| {
+            with_token(token.clone(), || {
+                let mut periodic = Periodic::new();
+                token.cancel();
+                for step in 1.. {
+                    periodic.check();
+                    assert!(step < Periodic::INTERVAL, "Cancelled after {step} steps");
+                }
+            })
+        });
+
+        assert_eq!(steps.unwrap_err().message, "Cancelled");
+    }
+}
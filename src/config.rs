@@ -0,0 +1,146 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::render::Format;
+
+/// Configuration file the runner reads from the current directory.
+pub const FILE_NAME: &str = "advent.toml";
+
+/// Runner defaults, command line flags take precedence over them.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Directory of `dayXX.txt` files replacing the embedded inputs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<PathBuf>,
+
+    /// Image format of the grids written with `--render`, answers are always printed as text
+    pub render_format: Format,
+
+    /// Seconds after which a part is abandoned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+
+    /// Number of threads used by parallel solutions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads: Option<usize>,
+
+    /// File of expected answers to check the results against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answers: Option<PathBuf>,
+
+    /// Directory of shared libraries adding days or replacing built-in ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugins: Option<PathBuf>,
+
+    /// Parameter overrides by day, e.g. `[params.14]` with `width = 11`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, BTreeMap<String, i64>>,
+}
+
+impl Config {
+    /// Reads the configuration at `path`.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+
+        Self::parse(&contents).with_context(|| format!("Invalid configuration {}", path.display()))
+    }
+
+    /// Reads the configuration at `path`, or returns the defaults if there is none.
+    pub fn load_or_default(path: &Path) -> anyhow::Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        let config = toml::from_str::<Self>(contents)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for day in self.params.keys() {
+            day.parse::<i32>()
+                .with_context(|| format!("Invalid day '{day}' in params"))?;
+        }
+
+        if self
+            .timeout
+            .is_some_and(|timeout| !(timeout > 0.0 && timeout.is_finite()))
+        {
+            anyhow::bail!("Timeout must be a positive number of seconds");
+        }
+
+        if self.threads == Some(0) {
+            anyhow::bail!("Thread count must be positive");
+        }
+
+        Ok(())
+    }
+
+    /// Parameter overrides for `day`.
+    pub fn params(&self, day: i32) -> BTreeMap<String, i64> {
+        self.params
+            .get(&day.to_string())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn to_toml(&self) -> anyhow::Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            "inputs = \"inputs\"\n\
+             render_format = \"svg\"\n\
+             timeout = 2.5\n\
+             [params.14]\n\
+             width = 11\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.inputs, Some(PathBuf::from("inputs")));
+        assert_eq!(config.render_format, Format::Svg);
+        assert_eq!(config.timeout, Some(2.5));
+        assert_eq!(config.threads, None);
+        assert_eq!(config.params(14).get("width"), Some(&11));
+        assert!(config.params(18).is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Config::parse("unknown = 1").is_err());
+        assert!(Config::parse("timeout = 0").is_err());
+        assert!(Config::parse("threads = 0").is_err());
+        assert!(Config::parse("[params.fourteen]").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut config = Config {
+            threads: Some(4),
+            ..Default::default()
+        };
+        config
+            .params
+            .insert("20".to_owned(), [("threshold".to_owned(), 50)].into());
+
+        assert_eq!(Config::parse(&config.to_toml().unwrap()).unwrap(), config);
+    }
+}
@@ -1,4 +1,9 @@
+use std::io::BufRead;
+
 use aoc_utils::{hashbrown::HashMap, AocError};
+use num::{BigInt, Num, Signed};
+
+use crate::{generate::Rng, input};
 
 const INPUT: &str = include_str!("input.txt");
 
@@ -6,74 +11,176 @@ pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    // The runner hands every input over in memory, reading it a line at a time only saves keeping
+    // the unused columns around
+    builder.add_part(|| part_1(input::get(INPUT).as_bytes(), columns()?));
+    builder.add_part(|| part_2(input::get(INPUT).as_bytes(), columns()?));
 }
 
-fn part_1(input: &str) -> anyhow::Result<i64> {
-    let mut a = Vec::new();
-    let mut b = Vec::new();
-
-    for line in input.lines() {
-        let (a_part, b_part) = line.split_once("   ").ok_or(AocError::InvalidInput)?;
-        let a_value = i64::from_str_radix(a_part, 10)?;
-        let b_value = i64::from_str_radix(b_part, 10)?;
+/// The two columns the parts compare, starting at 0.
+fn columns() -> Result<(usize, usize), AocError> {
+    Ok((input::param("left", 0)?, input::param("right", 1)?))
+}
 
-        a.push(a_value);
-        b.push(b_value);
-    }
+fn part_1(input: impl BufRead, columns: (usize, usize)) -> anyhow::Result<BigInt> {
+    let (mut a, mut b) = read_columns(input, columns)?;
 
-    a.sort();
-    b.sort();
+    a.sort_unstable();
+    b.sort_unstable();
 
     let total_distance = Iterator::zip(a.iter(), b.iter())
-        .map(|(&a, &b)| (a - b).abs())
+        .map(|(a, b)| (a - b).abs())
         .sum();
 
     Ok(total_distance)
 }
 
-fn part_2(input: &str) -> anyhow::Result<i64> {
-    let mut list = Vec::new();
-    let mut counts = HashMap::new();
+fn part_2(input: impl BufRead, columns: (usize, usize)) -> anyhow::Result<BigInt> {
+    let (list, other) = read_columns(input, columns)?;
 
-    for line in input.lines() {
-        let (a_part, b_part) = line.split_once("   ").ok_or(AocError::InvalidInput)?;
-        let a_value = i64::from_str_radix(a_part, 10)?;
-        let b_value = i64::from_str_radix(b_part, 10)?;
-
-        list.push(a_value);
+    let mut counts = HashMap::new();
+    for value in other {
         counts
-            .entry(b_value)
+            .entry(value)
             .and_modify(|count| {
                 *count += 1;
             })
-            .or_insert(1);
+            .or_insert(1u64);
     }
 
     let similarity_score = list
         .iter()
-        .map(|num| *num * counts.get(num).copied().unwrap_or(0))
+        .map(|num| num * counts.get(num).copied().unwrap_or(0))
         .sum();
 
     Ok(similarity_score)
 }
 
+/// Reads the `left` and `right` columns of a table of integers separated by any whitespace, a
+/// line at a time, keeping only those two columns.
+fn read_columns(
+    mut input: impl BufRead,
+    (left, right): (usize, usize),
+) -> anyhow::Result<(Vec<BigInt>, Vec<BigInt>)> {
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+
+    let mut width = None;
+    let mut line = String::new();
+    while input.read_line(&mut line)? != 0 {
+        let fields = line.split_whitespace();
+        let line_width = fields.clone().count();
+
+        if line_width > 0 {
+            // Every line must have as many columns as the first one
+            let width = *width.get_or_insert(line_width);
+            if line_width != width {
+                return Err(AocError::InvalidInput.into());
+            }
+
+            if left.max(right) >= width {
+                return Err(AocError::message(format!(
+                    "Column {} is out of range for {width} columns",
+                    left.max(right)
+                ))
+                .into());
+            }
+
+            let column = |index| BigInt::from_str_radix(fields.clone().nth(index).unwrap(), 10);
+            a.push(column(left)?);
+            b.push(column(right)?);
+        }
+
+        line.clear();
+    }
+
+    Ok((a, b))
+}
+
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let result = read_columns(input.as_bytes(), (0, 1));
+
+    // Lines must all have the same number of columns, the first two of them integers
+    let lines = input
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|fields| !fields.is_empty())
+        .collect::<Vec<_>>();
+    let malformed = lines.iter().any(|fields| {
+        fields.len() != lines[0].len()
+            || fields.len() < 2
+            || fields[..2]
+                .iter()
+                .any(|field| BigInt::from_str_radix(field, 10).is_err())
+    });
+    assert_eq!(result.is_err(), malformed, "{result:?}");
+
+    if let Ok((a, b)) = result {
+        assert_eq!((a.len(), b.len()), (lines.len(), lines.len()));
+    }
+}
+
+/// Generates `size` pairs of location IDs, the right list reusing about half of the left one.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let left = (0..size)
+        .map(|_| rng.range(10000..=99999))
+        .collect::<Vec<_>>();
+
+    let mut output = String::new();
+    for &a in left.iter() {
+        let b = if rng.chance(0.5) {
+            *rng.choose(&left)
+        } else {
+            rng.range(10000..=99999)
+        };
+
+        output.push_str(&format!("{a}   {b}\n"));
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
     const EXAMPLE_1: &str = include_str!("example.1.txt");
 
-    #[test]
-    fn test_part_1() {
-        let result = part_1(EXAMPLE_1).unwrap();
-        assert_eq!(result, 11);
+    const WIDE: &str = "100000000000000000000 1\t5\n1  100000000000000000000 5\n";
+
+    #[rstest]
+    #[case(EXAMPLE_1, (0, 1), "11")]
+    #[case(WIDE, (0, 1), "0")]
+    #[case(WIDE, (0, 2), "99999999999999999999")]
+    #[case(WIDE, (2, 2), "0")]
+    fn test_part_1(#[case] input: &str, #[case] columns: (usize, usize), #[case] expected: &str) {
+        let result = part_1(input.as_bytes(), columns).unwrap();
+        assert_eq!(result.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(EXAMPLE_1, (0, 1), "31")]
+    #[case(WIDE, (1, 0), "100000000000000000001")]
+    #[case(WIDE, (0, 2), "0")]
+    fn test_part_2(#[case] input: &str, #[case] columns: (usize, usize), #[case] expected: &str) {
+        let result = part_2(input.as_bytes(), columns).unwrap();
+        assert_eq!(result.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("1 2 3\n4 5\n", (0, 1))]
+    #[case("1 2\n3 4\n", (0, 2))]
+    #[case("1 x\n", (0, 1))]
+    fn test_invalid(#[case] input: &str, #[case] columns: (usize, usize)) {
+        assert!(part_1(input.as_bytes(), columns).is_err());
     }
 
     #[test]
-    fn test_part_2() {
-        let result = part_2(EXAMPLE_1).unwrap();
-        assert_eq!(result, 31);
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(input.as_bytes(), (0, 1)).unwrap();
+        part_2(input.as_bytes(), (0, 1)).unwrap();
     }
 }
@@ -1,4 +1,10 @@
+use std::ops::RangeInclusive;
+
+use aoc_utils::AocError;
 use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{dump, generate::Rng, input};
 
 const INPUT: &str = include_str!("input.txt");
 
@@ -6,90 +12,314 @@ pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT), &Rules::from_params()?));
+    builder.add_part(|| {
+        part_2(
+            &input::get(INPUT),
+            &Rules::from_params()?,
+            input::param("removals", 1)?,
+        )
+    });
 }
 
-fn part_1(input: &str) -> anyhow::Result<i64> {
-    let mut safe_report_count = 0;
-    for report in input.lines() {
-        let report = report
-            .split(' ')
-            .map(|part| i64::from_str_radix(part, 10))
-            .collect::<Result<Vec<_>, _>>()?;
+fn part_1(input: &str, rules: &Rules) -> anyhow::Result<i64> {
+    let reports = parse(input)?;
+    let (safe_report_count, _) = check_reports(&reports, rules, 0);
+    Ok(safe_report_count)
+}
 
-        if check_report_is_safe(&report) {
+fn part_2(input: &str, rules: &Rules, max_removals: usize) -> anyhow::Result<i64> {
+    let reports = parse(input)?;
+    let (safe_report_count, fixes) = check_reports(&reports, rules, max_removals);
+    dump::artifact("day02-removals", || fixes);
+    Ok(safe_report_count)
+}
+
+/// Counts the reports that are safe after removing at most `max_removals` levels, along with
+/// the levels removed from each report that needed it.
+fn check_reports(reports: &[Vec<i64>], rules: &Rules, max_removals: usize) -> (i64, Vec<Fix>) {
+    let mut safe_report_count = 0;
+    let mut fixes = Vec::new();
+    for (line, report) in reports.iter().enumerate() {
+        if let Some(removed) = find_removals(report, rules, max_removals) {
             safe_report_count += 1;
+
+            if !removed.is_empty() {
+                fixes.push(Fix { line, removed });
+            }
         }
     }
 
-    Ok(safe_report_count)
+    (safe_report_count, fixes)
 }
 
-fn part_2(input: &str) -> anyhow::Result<i64> {
-    let mut safe_report_count = 0;
-    'report: for report in input.lines() {
-        let report = report
+/// Parses one report of levels separated by spaces per line.
+fn parse(input: &str) -> anyhow::Result<Vec<Vec<i64>>> {
+    let mut reports = Vec::new();
+    for line in input.lines() {
+        let report = line
             .split(' ')
             .map(|part| i64::from_str_radix(part, 10))
             .collect::<Result<Vec<_>, _>>()?;
+        reports.push(report);
+    }
 
-        if check_report_is_safe(&report) {
-            safe_report_count += 1;
-            continue;
-        }
+    Ok(reports)
+}
 
-        for filtered_idx in 0..report.len() {
-            let mut filtered_report = report.clone();
-            filtered_report.remove(filtered_idx);
+/// A report made safe by removing levels, as written by `--dump`
+#[derive(Debug, Serialize)]
+struct Fix {
+    line: usize,
+    removed: Vec<usize>,
+}
 
-            if check_report_is_safe(&filtered_report) {
-                safe_report_count += 1;
-                continue 'report;
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rules {
+    /// Allowed differences between adjacent levels, in the report's direction
+    steps: RangeInclusive<i64>,
+    direction: Direction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Increasing,
+    Decreasing,
+    Either,
+}
+
+impl Rules {
+    /// Reads the `min_step`, `max_step` and `direction` (1, -1 or 0 for either) parameters.
+    fn from_params() -> anyhow::Result<Self> {
+        let steps = input::param("min_step", 1)?..=input::param("max_step", 3)?;
+        let direction = match input::param("direction", 0)? {
+            1 => Direction::Increasing,
+            -1 => Direction::Decreasing,
+            0 => Direction::Either,
+            direction => {
+                return Err(AocError::message(format!(
+                    "Invalid direction {direction}, expected 1, -1 or 0"
+                ))
+                .into())
             }
+        };
+
+        Ok(Self { steps, direction })
+    }
+
+    fn signs(&self) -> &'static [i64] {
+        match self.direction {
+            Direction::Increasing => &[1],
+            Direction::Decreasing => &[-1],
+            Direction::Either => &[1, -1],
         }
     }
+}
 
-    Ok(safe_report_count)
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            steps: 1..=3,
+            direction: Direction::Either,
+        }
+    }
 }
 
-fn check_report_is_safe(report: &[i64]) -> bool {
-    let mut sign = None;
-    for (&a, &b) in report.iter().tuple_windows() {
-        let delta = a - b;
-        let abs_delta = delta.abs();
-        if !(1..=3).contains(&abs_delta) {
-            return false;
+/// Finds the fewest levels to remove from `report` to make it safe, at most `max_removals`, and
+/// returns their indices.
+fn find_removals(report: &[i64], rules: &Rules, max_removals: usize) -> Option<Vec<usize>> {
+    rules
+        .signs()
+        .iter()
+        .filter_map(|&sign| find_removals_in_direction(report, rules, sign, max_removals))
+        .min_by_key(|removed| removed.len())
+}
+
+/// Like [`find_removals`] for reports going in the direction of `sign`, in O(n * max_removals).
+fn find_removals_in_direction(
+    report: &[i64],
+    rules: &Rules,
+    sign: i64,
+    max_removals: usize,
+) -> Option<Vec<usize>> {
+    let len = report.len();
+
+    // Fewest removals among the levels up to each one for a safe report ending there, and the
+    // level kept before it. At most `max_removals` levels can be skipped between two kept ones.
+    let mut removals = vec![None; len];
+    let mut previous = vec![None; len];
+    for j in 0..len {
+        if j <= max_removals {
+            removals[j] = Some(j);
         }
 
-        let delta_sign = delta.signum();
-        if let Some(sign) = sign {
-            if sign != delta_sign {
-                return false;
+        for i in j.saturating_sub(max_removals + 1)..j {
+            let Some(count) = removals[i] else {
+                continue;
+            };
+
+            if !rules.steps.contains(&((report[j] - report[i]) * sign)) {
+                continue;
             }
-        } else {
-            sign = Some(delta_sign);
+
+            let count = count + (j - i - 1);
+            if removals[j].is_none_or(|best| count < best) {
+                removals[j] = Some(count);
+                previous[j] = Some(i);
+            }
+        }
+    }
+
+    let (mut last, _) = (len.saturating_sub(max_removals + 1)..len)
+        .filter_map(|j| Some((j, removals[j]? + (len - 1 - j))))
+        .filter(|&(_, count)| count <= max_removals)
+        .min_by_key(|&(_, count)| count)
+        .or((len == 0).then_some((0, 0)))?;
+
+    let mut kept = vec![false; len];
+    while let Some(level) = kept.get_mut(last) {
+        *level = true;
+        let Some(i) = previous[last] else {
+            break;
+        };
+        last = i;
+    }
+
+    Some((0..len).filter(|&i| !kept[i]).collect())
+}
+
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let result = parse(input);
+
+    // Levels are integers, and there must be one report per line
+    let is_level_char = |c: char| c.is_ascii_digit() || matches!(c, ' ' | '+' | '-');
+    if !input.lines().flat_map(str::chars).all(is_level_char) {
+        assert!(result.is_err(), "Invalid characters should be rejected");
+    }
+
+    if let Ok(reports) = result {
+        assert_eq!(reports.len(), input.lines().count());
+    }
+}
+
+/// Generates `size` reports, each either safe or with one level perturbed.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..size {
+        let len = rng.range(5..=8) as usize;
+        let direction = if rng.chance(0.5) { 1 } else { -1 };
+
+        let mut levels = vec![rng.range(25..=75)];
+        for _ in 1..len {
+            let previous = levels[levels.len() - 1];
+            levels.push(previous + direction * rng.range(1..=3));
+        }
+
+        if rng.chance(0.5) {
+            let index = rng.index(len);
+            levels[index] += rng.range(-4..=4);
         }
+
+        output.push_str(&levels.iter().join(" "));
+        output.push('\n');
     }
 
-    true
+    output
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
     const EXAMPLE_1: &str = include_str!("example.1.txt");
 
     #[test]
     fn test_part_1() {
-        let result = part_1(EXAMPLE_1).unwrap();
+        let result = part_1(EXAMPLE_1, &Rules::default()).unwrap();
         assert_eq!(result, 2);
     }
 
     #[test]
     fn test_part_2() {
-        let result = part_2(EXAMPLE_1).unwrap();
+        let result = part_2(EXAMPLE_1, &Rules::default(), 1).unwrap();
         assert_eq!(result, 4);
     }
+
+    #[rstest]
+    #[case(&[7, 1, 2, 3, 4], 1, Some(vec![0]))]
+    #[case(&[1, 2, 7, 8, 9], 1, None)]
+    #[case(&[1, 2, 7, 8, 9], 2, Some(vec![0, 1]))]
+    #[case(&[1, 2, 3, 9, 4], 1, Some(vec![3]))]
+    #[case(&[5, 4, 3, 2, 9], 1, Some(vec![4]))]
+    #[case(&[5], 0, Some(vec![]))]
+    #[case(&[], 0, Some(vec![]))]
+    fn test_find_removals(
+        #[case] report: &[i64],
+        #[case] max_removals: usize,
+        #[case] expected: Option<Vec<usize>>,
+    ) {
+        assert_eq!(
+            find_removals(report, &Rules::default(), max_removals),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_rules() {
+        let report = [1, 5, 9, 13];
+        let wide = Rules {
+            steps: 1..=4,
+            direction: Direction::Either,
+        };
+        assert_eq!(find_removals(&report, &wide, 0), Some(vec![]));
+
+        let decreasing = Rules {
+            direction: Direction::Decreasing,
+            ..wide
+        };
+        assert_eq!(find_removals(&report, &decreasing, 2), None);
+    }
+
+    #[test]
+    fn test_find_removals_matches_brute_force() {
+        let is_safe = |report: &[i64]| {
+            [1, -1].iter().any(|sign| {
+                report
+                    .iter()
+                    .tuple_windows()
+                    .all(|(a, b)| (1..=3).contains(&((b - a) * sign)))
+            })
+        };
+
+        let mut rng = Rng::new(2);
+        for _ in 0..500 {
+            let report = (0..rng.range(1..=8))
+                .map(|_| rng.range(0..=12))
+                .collect::<Vec<_>>();
+
+            for max_removals in 0..=3 {
+                let expected = (0..=max_removals.min(report.len())).find(|&count| {
+                    (0..report.len()).combinations(count).any(|removed| {
+                        let kept = (0..report.len())
+                            .filter(|i| !removed.contains(i))
+                            .map(|i| report[i])
+                            .collect::<Vec<_>>();
+                        is_safe(&kept)
+                    })
+                });
+
+                let removed = find_removals(&report, &Rules::default(), max_removals);
+                assert_eq!(removed.as_ref().map(Vec::len), expected, "{report:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input, &Rules::default()).unwrap();
+        part_2(&input, &Rules::default(), 1).unwrap();
+    }
 }
@@ -0,0 +1,32 @@
+use super::lexer::Instruction;
+
+/// An instruction of the language, with the number of arguments it takes.
+///
+/// `execute` returns whether the instruction counted towards the total.
+#[derive(Debug, Clone, Copy)]
+pub struct Definition {
+    pub name: &'static str,
+    pub arity: usize,
+    pub execute: fn(&mut Machine, &[i64]) -> bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+    pub enabled: bool,
+    pub total: i64,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            total: 0,
+        }
+    }
+}
+
+impl Machine {
+    pub fn execute(&mut self, instruction: &Instruction) -> bool {
+        (instruction.definition.execute)(self, &instruction.args)
+    }
+}
@@ -0,0 +1,133 @@
+use serde::Serialize;
+
+use super::interpreter::Definition;
+
+/// Most digits an argument can have.
+const MAX_DIGITS: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Instruction<'a> {
+    /// Byte offset of the instruction's name in the input
+    pub offset: usize,
+    #[serde(rename = "name", serialize_with = "serialize_name")]
+    pub definition: &'a Definition,
+    pub args: Vec<i64>,
+}
+
+fn serialize_name<S>(definition: &&Definition, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(definition.name)
+}
+
+/// Finds the instructions of `definitions` in corrupted input arriving in chunks, skipping
+/// anything else.
+///
+/// Like a regex, the leftmost instruction wins and the search resumes after its end. At the same
+/// offset, the first definition that matches wins. Instructions can straddle chunks: the end of a
+/// chunk that could still start one is held back until the next chunk arrives.
+#[derive(Debug, Clone)]
+pub struct Lexer<'a> {
+    definitions: &'a [Definition],
+    /// Input not lexed yet
+    pending: Vec<u8>,
+    /// Offset of `pending` in the input
+    offset: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(definitions: &'a [Definition]) -> Self {
+        Self {
+            definitions,
+            pending: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    /// Lexes the next `chunk` of input, calling `f` with every instruction completed.
+    pub fn feed(&mut self, chunk: &[u8], f: impl FnMut(Instruction<'a>)) {
+        self.pending.extend_from_slice(chunk);
+        self.scan(false, f);
+    }
+
+    /// Lexes whatever was held back once the input has ended.
+    pub fn finish(mut self, f: impl FnMut(Instruction<'a>)) {
+        self.scan(true, f);
+    }
+
+    fn scan(&mut self, is_end: bool, mut f: impl FnMut(Instruction<'a>)) {
+        let mut start = 0;
+        'scan: while start < self.pending.len() {
+            for definition in self.definitions {
+                match lex_instruction(&self.pending[start..], definition) {
+                    Ok((args, len)) => {
+                        f(Instruction {
+                            offset: self.offset + start,
+                            definition,
+                            args,
+                        });
+                        start += len;
+                        continue 'scan;
+                    }
+                    // Later definitions only win if this one doesn't match
+                    Err(NoMatch::Incomplete) if !is_end => break 'scan,
+                    Err(_) => {}
+                }
+            }
+
+            start += 1;
+        }
+
+        self.pending.drain(..start);
+        self.offset += start;
+    }
+}
+
+/// Why there is no instruction at an offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NoMatch {
+    Mismatch,
+    /// The input ended before the instruction could be told apart from noise
+    Incomplete,
+}
+
+/// Matches `name(a,b,...)` at the start of `input`, returning the arguments and length.
+fn lex_instruction(input: &[u8], definition: &Definition) -> Result<(Vec<i64>, usize), NoMatch> {
+    let mut rest = strip(input, definition.name.as_bytes())?;
+    rest = strip(rest, b"(")?;
+
+    let mut args = Vec::with_capacity(definition.arity);
+    for i in 0..definition.arity {
+        if i > 0 {
+            rest = strip(rest, b",")?;
+        }
+
+        let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+        if digits > MAX_DIGITS {
+            return Err(NoMatch::Mismatch);
+        } else if digits == rest.len() {
+            return Err(NoMatch::Incomplete);
+        } else if digits == 0 {
+            return Err(NoMatch::Mismatch);
+        }
+
+        let arg = rest[..digits]
+            .iter()
+            .fold(0, |arg, digit| arg * 10 + (digit - b'0') as i64);
+        args.push(arg);
+        rest = &rest[digits..];
+    }
+
+    rest = strip(rest, b")")?;
+
+    Ok((args, input.len() - rest.len()))
+}
+
+fn strip<'i>(input: &'i [u8], prefix: &[u8]) -> Result<&'i [u8], NoMatch> {
+    match input.strip_prefix(prefix) {
+        Some(rest) => Ok(rest),
+        None if prefix.starts_with(input) => Err(NoMatch::Incomplete),
+        None => Err(NoMatch::Mismatch),
+    }
+}
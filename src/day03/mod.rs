@@ -1,75 +1,256 @@
-use regex::Regex;
+use std::io::{self, Read};
+
+use serde::Serialize;
+
+use crate::{dump, generate::Rng, input};
+
+use self::{
+    interpreter::{Definition, Machine},
+    lexer::{Instruction, Lexer},
+};
+
+mod interpreter;
+mod lexer;
 
 const INPUT: &str = include_str!("input.txt");
 
+/// Size of the chunks read from readers
+const CHUNK_SIZE: usize = 64 * 1024;
+
+const MUL: Definition = Definition {
+    name: "mul",
+    arity: 2,
+    execute: |machine, args| {
+        if machine.enabled {
+            machine.total += args[0] * args[1];
+        }
+        machine.enabled
+    },
+};
+
+const DO: Definition = Definition {
+    name: "do",
+    arity: 0,
+    execute: |machine, _| {
+        machine.enabled = true;
+        false
+    },
+};
+
+const DONT: Definition = Definition {
+    name: "don't",
+    arity: 0,
+    execute: |machine, _| {
+        machine.enabled = false;
+        false
+    },
+};
+
+/// Instructions each part understands, tried in this order at every offset
+const PART_1: &[Definition] = &[MUL];
+const PART_2: &[Definition] = &[MUL, DO, DONT];
+
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<i64> {
-    let regex = Regex::new("mul\\(([0-9]{1,3}),([0-9]{1,3})\\)").unwrap();
-
-    let mut total = 0;
-    for captures in regex.captures_iter(input) {
-        let a = captures
-            .get(1)
-            .expect("Regex has 2 capturing groups")
-            .as_str();
-        let a = i64::from_str_radix(a, 10)?;
-        let b = captures
-            .get(2)
-            .expect("Regex has 2 capturing groups")
-            .as_str();
-        let b = i64::from_str_radix(b, 10)?;
-
-        total += a * b;
-    }
+    evaluate_part(input, PART_1, "day03-part1-instructions")
+}
+
+fn part_2(input: &str) -> anyhow::Result<i64> {
+    evaluate_part(input, PART_2, "day03-part2-instructions")
+}
 
+fn evaluate_part(input: &str, definitions: &[Definition], name: &str) -> anyhow::Result<i64> {
+    let mut steps = Vec::new();
+    let total = evaluate_reader(input.as_bytes(), definitions, |step| {
+        if dump::is_enabled() {
+            steps.push(step);
+        }
+    })?;
+
+    dump::artifact(name, || steps);
     Ok(total)
 }
 
-fn part_2(input: &str) -> anyhow::Result<i64> {
-    let regex = Regex::new("do\\(\\)|don't\\(\\)|mul\\(([0-9]{1,3}),([0-9]{1,3})\\)").unwrap();
-
-    let mut total = 0;
-    let mut is_mul_enabled = true;
-    for captures in regex.captures_iter(input) {
-        let whole_match = captures.get(0).unwrap().as_str();
-
-        if whole_match.starts_with("don't") {
-            is_mul_enabled = false;
-        } else if whole_match.starts_with("do") {
-            is_mul_enabled = true;
-        } else if is_mul_enabled && whole_match.starts_with("mul") {
-            let a = captures
-                .get(1)
-                .expect("Regex has 2 capturing groups")
-                .as_str();
-            let a = i64::from_str_radix(a, 10)?;
-            let b = captures
-                .get(2)
-                .expect("Regex has 2 capturing groups")
-                .as_str();
-            let b = i64::from_str_radix(b, 10)?;
-
-            total += a * b;
+/// Runs the instructions of `definitions` found in input arriving in `chunks`, calling `on_step`
+/// with every instruction run, and returns the total.
+///
+/// Whether `mul` is enabled carries over from one chunk to the next.
+fn evaluate_chunks<'a, I>(
+    chunks: I,
+    definitions: &'a [Definition],
+    mut on_step: impl FnMut(Step<'a>),
+) -> i64
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let mut machine = Machine::default();
+    let mut run = |instruction: Instruction<'a>| {
+        let counted = machine.execute(&instruction);
+        on_step(Step {
+            instruction,
+            counted,
+        });
+    };
+
+    let mut lexer = Lexer::new(definitions);
+    for chunk in chunks {
+        lexer.feed(chunk.as_ref(), &mut run);
+    }
+    lexer.finish(&mut run);
+
+    machine.total
+}
+
+/// Like [`evaluate_chunks`], reading the input from `reader` a chunk at a time.
+fn evaluate_reader<'a>(
+    mut reader: impl Read,
+    definitions: &'a [Definition],
+    on_step: impl FnMut(Step<'a>),
+) -> io::Result<i64> {
+    let mut error = None;
+    let chunks = std::iter::from_fn(|| {
+        let mut chunk = vec![0; CHUNK_SIZE];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) => return None,
+                Ok(len) => {
+                    chunk.truncate(len);
+                    return Some(chunk);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    error = Some(e);
+                    return None;
+                }
+            }
         }
+    });
+
+    let total = evaluate_chunks(chunks, definitions, on_step);
+    match error {
+        Some(e) => Err(e),
+        None => Ok(total),
     }
+}
 
-    Ok(total)
+/// An instruction as run, as written by `--dump`
+#[derive(Debug, Clone, Serialize)]
+struct Step<'a> {
+    #[serde(flatten)]
+    instruction: Instruction<'a>,
+    counted: bool,
+}
+
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    // Any input is valid corrupted memory, so check that lexing it a byte at a time finds the
+    // same instructions as lexing it at once
+    let lex = |chunk_size: usize| {
+        let mut found = Vec::new();
+        let mut push = |instruction: Instruction| {
+            found.push((
+                instruction.offset,
+                instruction.definition.name,
+                instruction.args,
+            ));
+        };
+
+        let mut lexer = Lexer::new(PART_2);
+        for chunk in input.as_bytes().chunks(chunk_size) {
+            lexer.feed(chunk, &mut push);
+        }
+        lexer.finish(&mut push);
+        found
+    };
+
+    assert_eq!(lex(1), lex(input.len().max(1)));
+}
+
+/// Generates `size` instructions, some of them corrupted, separated by random noise.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const NOISE: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789 ()[]{}<>,;:'!@#$%^&*-+/?~\n";
+
+    let mut output = String::new();
+    for _ in 0..size {
+        for _ in 0..rng.range(0..=12) {
+            output.push(*rng.choose(NOISE) as char);
+        }
+
+        let a = rng.range(1..=999);
+        let b = rng.range(1..=999);
+        let instruction = match rng.below(10) {
+            0 => "do()".to_owned(),
+            1 => "don't()".to_owned(),
+            2 => format!("mul({a}, {b})"),
+            3 => format!("mul[{a},{b}]"),
+            4 => format!("mul({a},{b}"),
+            5 => format!("mul({a},{b}{})", rng.below(10)),
+            _ => format!("mul({a},{b})"),
+        };
+
+        output.push_str(&instruction);
+    }
+
+    output.push('\n');
+    output
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
     const EXAMPLE_1: &str = include_str!("example.1.txt");
     const EXAMPLE_2: &str = include_str!("example.2.txt");
 
+    fn tokenize<'a>(input: &str, definitions: &'a [Definition]) -> Vec<Instruction<'a>> {
+        let mut instructions = Vec::new();
+        let mut lexer = Lexer::new(definitions);
+        lexer.feed(input.as_bytes(), |instruction| {
+            instructions.push(instruction)
+        });
+        lexer.finish(|instruction| instructions.push(instruction));
+        instructions
+    }
+
+    /// Evaluates `chunks`, returning the total and the offsets of the instructions counted.
+    fn evaluate<I>(chunks: I, definitions: &[Definition]) -> (i64, Vec<usize>)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut counted = Vec::new();
+        let total = evaluate_chunks(chunks, definitions, |step| {
+            if step.counted {
+                counted.push(step.instruction.offset);
+            }
+        });
+        (total, counted)
+    }
+
+    /// Reads at most `len` bytes at a time.
+    struct Trickle<'a> {
+        input: &'a [u8],
+        len: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self.len.min(buf.len()).min(self.input.len());
+            buf[..len].copy_from_slice(&self.input[..len]);
+            self.input = &self.input[len..];
+            Ok(len)
+        }
+    }
+
     #[test]
     fn test_part_1() {
         let result = part_1(EXAMPLE_1).unwrap();
@@ -81,4 +262,88 @@ mod tests {
         let result = part_2(EXAMPLE_2).unwrap();
         assert_eq!(result, 48)
     }
+
+    #[test]
+    fn test_tokenize() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let instructions = tokenize(input, PART_2)
+            .into_iter()
+            .map(|i| (i.offset, i.definition.name, i.args))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            instructions,
+            vec![
+                (1, "mul", vec![2, 4]),
+                (20, "don't", vec![]),
+                (28, "mul", vec![5, 5]),
+                (48, "mul", vec![11, 8]),
+                (59, "do", vec![]),
+                (64, "mul", vec![8, 5]),
+            ]
+        );
+
+        assert_eq!(evaluate([input], PART_2), (48, vec![1, 64]));
+    }
+
+    #[test]
+    fn test_tokenize_arguments() {
+        let lex = |input: &str| {
+            tokenize(input, PART_1)
+                .into_iter()
+                .map(|i| i.args)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            lex("mul(1,999)mul(1000,1)mul(1, 2)mul(mul(3,4)"),
+            vec![vec![1, 999], vec![3, 4]]
+        );
+        assert_eq!(lex("mul(2,3"), Vec::<Vec<i64>>::new());
+    }
+
+    #[rstest]
+    #[case(EXAMPLE_1, PART_1)]
+    #[case(EXAMPLE_2, PART_2)]
+    #[case("mul(1,2)don't()mul(3,4)do()mul(555,666)", PART_2)]
+    fn test_chunks(#[case] input: &str, #[case] definitions: &[Definition]) {
+        let expected = evaluate([input], definitions);
+
+        let input = input.as_bytes();
+        for split in 0..=input.len() {
+            let (a, b) = input.split_at(split);
+            assert_eq!(evaluate([a, b], definitions), expected, "split at {split}");
+        }
+
+        assert_eq!(evaluate(input.chunks(1), definitions), expected);
+    }
+
+    #[rstest]
+    fn test_reader(#[values(1, 2, 3, 7)] len: usize) {
+        let input = EXAMPLE_2.as_bytes();
+        let total = evaluate_reader(Trickle { input, len }, PART_2, |_| {}).unwrap();
+        assert_eq!(total, 48);
+    }
+
+    #[test]
+    fn test_new_instruction() {
+        const ADD3: Definition = Definition {
+            name: "add3",
+            arity: 3,
+            execute: |machine, args| {
+                machine.total += args.iter().sum::<i64>();
+                true
+            },
+        };
+
+        let input = "add3(1,2,3)mul(2,2)add3(1,2)add3(4,5,6)";
+        assert_eq!(evaluate([input], &[MUL, ADD3]), (25, vec![0, 11, 28]));
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
@@ -1,112 +1,46 @@
 use allocator_api2::vec::Vec;
 use aoc_utils::{
     grid::{Coordinates, Grid},
-    numerics::min_max,
     AocError,
 };
 
+use crate::{generate::Rng, input};
+
+use self::{
+    stencil::{Stencil, Transform},
+    word_search::{SearchOptions, WordSearch},
+};
+
+pub mod stencil;
+mod word_search;
+
 const INPUT: &str = include_str!("input.txt");
 
+/// Two `MAS` crossing on their `A`, in any orientation for part 2
+const X_MAS: &str = "M.S\n.A.\nM.S";
+
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<i64> {
     let grid = parse(input)?;
-
-    let mut count = 0;
-    for index in 0..grid.len() {
-        let coordinates = grid.get_coordinates(index).expect("Index should be valid");
-        count += count_word_instances(&grid, coordinates, "XMAS");
-    }
-
+    let search = WordSearch::new(["XMAS"], SearchOptions::default())?;
+    let count = search.find_all(&grid).len() as i64;
     Ok(count)
 }
 
-fn count_word_instances(grid: &Grid<char>, start_coordinates: Coordinates, word: &str) -> i64 {
-    let (head, tail) = {
-        let mut chars = word.chars();
-        let head = chars.next();
-        (head, chars.as_str())
-    };
-
-    let start = grid.get(start_coordinates).copied();
-    if start.is_none() || start != head {
-        return 0;
-    }
-
-    Direction::ALL
-        .into_iter()
-        .filter_map(|direction| {
-            direction
-                .offset(start_coordinates)
-                .map(|coordinates| (direction, coordinates))
-        })
-        .map(|(direction, coordinates)| is_word_instance(grid, coordinates, tail, direction) as i64)
-        .sum()
-}
-
-fn is_word_instance(
-    grid: &Grid<char>,
-    start_coordinates: Coordinates,
-    word: &str,
-    direction: Direction,
-) -> bool {
-    let mut coordinates = start_coordinates;
-    for (index, expected) in word.chars().enumerate() {
-        if index != 0 {
-            if let Some(next) = direction.offset(coordinates) {
-                coordinates = next;
-            } else {
-                return false;
-            }
-        }
-
-        if let Some(&actual) = grid.get(coordinates) {
-            if expected != actual {
-                return false;
-            }
-        } else {
-            return false;
-        }
-    }
-
-    true
-}
-
 fn part_2(input: &str) -> anyhow::Result<i64> {
     let grid = parse(input)?;
-
-    let mut count = 0;
-    for index in 0..grid.len() {
-        let coordinates = grid.get_coordinates(index).expect("Index should be valid");
-        count += is_x_mas(&grid, coordinates) as i64;
-    }
-
+    let stencil = Stencil::parse(X_MAS)?;
+    let count = stencil.count_matches(&grid, &Transform::ALL) as i64;
     Ok(count)
 }
 
-fn is_x_mas(grid: &Grid<char>, coordinates: Coordinates) -> bool {
-    let center = grid.get(coordinates);
-    if !matches!(center, Some('A')) {
-        return false;
-    }
-
-    let neighbors = Direction::INTERCARDINAL.map(|direction| {
-        direction
-            .offset(coordinates)
-            .and_then(|coordinates| grid.get(coordinates).copied())
-    });
-
-    let down = min_max(neighbors[0], neighbors[2]);
-    let up = min_max(neighbors[1], neighbors[3]);
-    down == (Some('M'), Some('S')) && up == (Some('M'), Some('S'))
-}
-
 fn parse(input: &str) -> Result<Grid<char>, AocError> {
     let line_length = input
         .lines()
@@ -120,6 +54,11 @@ fn parse(input: &str) -> Result<Grid<char>, AocError> {
     let mut lines = 0;
     for line in input.lines() {
         lines += 1;
+
+        if line.chars().count() != line_length {
+            return Err(AocError::InvalidInput);
+        }
+
         data.extend(line.chars());
     }
 
@@ -127,6 +66,7 @@ fn parse(input: &str) -> Result<Grid<char>, AocError> {
     Ok(grid)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
     North,
     NorthEast,
@@ -139,23 +79,18 @@ enum Direction {
 }
 
 impl Direction {
-    const ALL: [Self; 8] = [
-        Self::North,
-        Self::NorthEast,
-        Self::East,
-        Self::SouthEast,
-        Self::South,
-        Self::SouthWest,
-        Self::West,
-        Self::NorthWest,
-    ];
-
-    const INTERCARDINAL: [Self; 4] = [
-        Self::NorthEast,
-        Self::SouthEast,
-        Self::SouthWest,
-        Self::NorthWest,
-    ];
+    pub const fn opposite(&self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::NorthEast => Self::SouthWest,
+            Self::East => Self::West,
+            Self::SouthEast => Self::NorthWest,
+            Self::South => Self::North,
+            Self::SouthWest => Self::NorthEast,
+            Self::West => Self::East,
+            Self::NorthWest => Self::SouthEast,
+        }
+    }
 
     pub fn offset(&self, coordinates: Coordinates) -> Option<Coordinates> {
         match self {
@@ -203,12 +138,72 @@ impl Direction {
     }
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a `size` by `size` grid of random `XMAS` letters.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const LETTERS: [char; 4] = ['X', 'M', 'A', 'S'];
+
+    let size = size.max(4);
+    let mut output = String::new();
+    for _ in 0..size {
+        for _ in 0..size {
+            output.push(*rng.choose(&LETTERS));
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
     const EXAMPLE_1: &str = include_str!("example.1.txt");
 
+    /// Counts `word` starting at every cell and going in every direction, one letter at a time.
+    fn count_naively(grid: &Grid<char>, word: &str) -> usize {
+        use Direction::*;
+
+        let mut count = 0;
+        for index in 0..grid.len() {
+            let start = grid.get_coordinates(index).unwrap();
+            for direction in [
+                North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest,
+            ] {
+                let cells = std::iter::successors(Some(start), |&c| direction.offset(c));
+                let letters = cells.map_while(|c| grid.get(c).copied());
+                if letters.take(word.chars().count()).eq(word.chars()) {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    fn search(
+        input: &str,
+        words: &[&str],
+        options: SearchOptions,
+    ) -> std::vec::Vec<(u32, u32, Direction, String)> {
+        let grid = parse(input).unwrap();
+        let search = WordSearch::new(words.iter().copied(), options).unwrap();
+        let mut matches = search
+            .find_all(&grid)
+            .into_iter()
+            .map(|m| (m.start.x, m.start.y, m.direction, m.word.to_owned()))
+            .collect::<std::vec::Vec<_>>();
+        matches.sort_by_key(|m| (m.1, m.0, m.3.clone(), m.2 as u8));
+        matches
+    }
+
     #[test]
     fn test_part_1() {
         let result = part_1(EXAMPLE_1).unwrap();
@@ -220,4 +215,164 @@ mod tests {
         let result = part_2(EXAMPLE_1).unwrap();
         assert_eq!(result, 9)
     }
+
+    #[rstest]
+    #[case(&["XMAS"])]
+    #[case(&["MAS", "XM", "SAS", "AMA"])]
+    #[case(&["MMMSXXMASM"])]
+    fn test_word_search(#[case] words: &[&str]) {
+        for seed in 0..5 {
+            let input = generate(&mut Rng::new(seed), 12);
+            let grid = parse(&input).unwrap();
+            let search = WordSearch::new(words.iter().copied(), SearchOptions::default()).unwrap();
+            let matches = search.find_all(&grid);
+
+            for &word in words {
+                let found = matches.iter().filter(|m| m.word == word).count();
+                assert_eq!(found, count_naively(&grid, word), "{word}, seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_match_locations() {
+        use Direction::*;
+
+        let input = "XMAS\nM..A\nA..M\nSAMX\n";
+        assert_eq!(
+            search(input, &["XMAS"], SearchOptions::default()),
+            vec![
+                (0, 0, East, "XMAS".to_owned()),
+                (0, 0, South, "XMAS".to_owned()),
+                (3, 3, North, "XMAS".to_owned()),
+                (3, 3, West, "XMAS".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_options() {
+        let input = "XMASAMX\n";
+        let overlapping = SearchOptions::default();
+        let separate = SearchOptions {
+            overlapping: false,
+            ..overlapping
+        };
+        assert_eq!(search(input, &["XMAS"], overlapping).len(), 2);
+        assert_eq!(search(input, &["XMAS"], separate).len(), 1);
+
+        let deduplicated = SearchOptions {
+            dedup_palindromes: true,
+            ..overlapping
+        };
+        assert_eq!(search(input, &["MASAM"], overlapping).len(), 2);
+        assert_eq!(search(input, &["MASAM"], deduplicated).len(), 1);
+
+        assert!(WordSearch::new(["X"], overlapping).is_err());
+    }
+
+    #[test]
+    fn test_x_mas() {
+        let is_x_mas = |grid: &Grid<char>, center: Coordinates| {
+            let corner = |dx: i32, dy: i32| {
+                let x = center.x.checked_add_signed(dx)?;
+                let y = center.y.checked_add_signed(dy)?;
+                grid.get(Coordinates::new(x, y)).copied()
+            };
+
+            let is_mas = |a, b| matches!((a, b), (Some('M'), Some('S')) | (Some('S'), Some('M')));
+            grid.get(center) == Some(&'A')
+                && is_mas(corner(-1, -1), corner(1, 1))
+                && is_mas(corner(1, -1), corner(-1, 1))
+        };
+
+        let stencil = Stencil::parse(X_MAS).unwrap();
+        for seed in 0..5 {
+            let grid = parse(&generate(&mut Rng::new(seed), 12)).unwrap();
+            let expected = (0..grid.len())
+                .filter(|&index| is_x_mas(&grid, grid.get_coordinates(index).unwrap()))
+                .count();
+            assert_eq!(stencil.count_matches(&grid, &Transform::ALL), expected);
+        }
+    }
+
+    #[test]
+    fn test_transforms() {
+        let stencil = Stencil::parse("AB.\nCDE").unwrap();
+        let rows = |stencil: &Stencil| {
+            (0..stencil.height())
+                .map(|y| {
+                    (0..stencil.width())
+                        .map(|x| stencil.get(x, y).unwrap_or(stencil::WILDCARD))
+                        .collect::<String>()
+                })
+                .collect::<std::vec::Vec<_>>()
+        };
+
+        assert_eq!(
+            rows(&stencil.transformed(Transform::Rotate90)),
+            ["CA", "DB", "E."]
+        );
+        assert_eq!(
+            rows(&stencil.transformed(Transform::Rotate180)),
+            ["EDC", ".BA"]
+        );
+        assert_eq!(
+            rows(&stencil.transformed(Transform::Rotate270)),
+            [".E", "BD", "AC"]
+        );
+        assert_eq!(
+            rows(&stencil.transformed(Transform::FlipHorizontal)),
+            [".BA", "EDC"]
+        );
+        assert_eq!(
+            rows(&stencil.transformed(Transform::FlipVertical)),
+            ["CDE", "AB."]
+        );
+        assert_eq!(
+            rows(&stencil.transformed(Transform::Transpose)),
+            ["AC", "BD", ".E"]
+        );
+        assert_eq!(
+            rows(&stencil.transformed(Transform::AntiTranspose)),
+            ["E.", "DB", "CA"]
+        );
+
+        for transform in Transform::ALL {
+            let twice = stencil.transformed(transform).transformed(transform);
+            let is_quarter_turn = matches!(transform, Transform::Rotate90 | Transform::Rotate270);
+            assert_eq!(twice == stencil, !is_quarter_turn, "{transform:?}");
+        }
+    }
+
+    #[test]
+    fn test_find_matches() {
+        let grid = parse("XMAS\nSAMX\nMMXA\n").unwrap();
+        let stencil = Stencil::parse("XM").unwrap();
+
+        let matches = stencil.find_matches(&grid, &Transform::ROTATIONS);
+        let found = matches
+            .iter()
+            .map(|m| (m.origin.x, m.origin.y, m.transform))
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                (0, 0, Transform::Identity),
+                (2, 1, Transform::Rotate180),
+                (2, 1, Transform::Rotate270),
+                (1, 2, Transform::Rotate180),
+            ]
+        );
+
+        assert_eq!(stencil.count_matches(&grid, &[Transform::Identity]), 1);
+        assert!(Stencil::parse("AB\nC").is_err());
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
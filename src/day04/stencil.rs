@@ -0,0 +1,198 @@
+use aoc_utils::{
+    grid::{Coordinates, Grid},
+    AocError,
+};
+
+/// Marks the cells of a stencil that match any letter.
+pub const WILDCARD: char = '.';
+
+/// A 2D pattern of letters, with wildcard cells that match anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stencil {
+    width: u32,
+    height: u32,
+    cells: Vec<Option<char>>,
+}
+
+/// One of the eight rotations and reflections of a stencil.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    Identity,
+    /// A quarter turn clockwise
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrored left to right
+    FlipHorizontal,
+    /// Mirrored top to bottom
+    FlipVertical,
+    /// Mirrored along the diagonal from the top left
+    Transpose,
+    /// Mirrored along the diagonal from the top right
+    AntiTranspose,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StencilMatch {
+    /// Coordinates of the top left corner of the transformed stencil
+    pub origin: Coordinates,
+    pub transform: Transform,
+}
+
+impl Stencil {
+    /// Parses rows of letters of the same length, with [`WILDCARD`] for cells matching anything.
+    pub fn parse(pattern: &str) -> Result<Self, AocError> {
+        let width = pattern
+            .lines()
+            .next()
+            .ok_or(AocError::EmptyInput)?
+            .chars()
+            .count();
+        if width == 0 {
+            return Err(AocError::EmptyInput);
+        }
+
+        let mut cells = Vec::new();
+        let mut height = 0;
+        for line in pattern.lines() {
+            if line.chars().count() != width {
+                return Err(AocError::InvalidInput);
+            }
+
+            cells.extend(line.chars().map(|c| (c != WILDCARD).then_some(c)));
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width as u32,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The letter the cell at `x`, `y` must have, or `None` for wildcards.
+    pub fn get(&self, x: u32, y: u32) -> Option<char> {
+        self.cells[(y * self.width + x) as usize]
+    }
+
+    pub fn transformed(&self, transform: Transform) -> Self {
+        let (width, height) = transform.dimensions(self.width, self.height);
+
+        let mut cells = vec![None; self.cells.len()];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (tx, ty) = transform.apply(x, y, self.width, self.height);
+                cells[(ty * width + tx) as usize] = self.get(x, y);
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Whether the stencil matches `grid` with its top left corner at `origin`.
+    pub fn matches_at(&self, grid: &Grid<char>, origin: Coordinates) -> bool {
+        if origin.x + self.width > grid.width() || origin.y + self.height > grid.height() {
+            return false;
+        }
+
+        (0..self.height).all(|y| {
+            (0..self.width).all(|x| match self.get(x, y) {
+                Some(expected) => grid[Coordinates::new(origin.x + x, origin.y + y)] == expected,
+                None => true,
+            })
+        })
+    }
+
+    /// Every place `grid` matches the stencil under one of `transforms`.
+    ///
+    /// Transforms that give the same stencil, like the rotations of a symmetric one, only match
+    /// once per place, under the first of them.
+    pub fn find_matches(&self, grid: &Grid<char>, transforms: &[Transform]) -> Vec<StencilMatch> {
+        let mut variants: Vec<(Transform, Stencil)> = Vec::new();
+        for &transform in transforms {
+            let stencil = self.transformed(transform);
+            if variants.iter().all(|(_, variant)| *variant != stencil) {
+                variants.push((transform, stencil));
+            }
+        }
+
+        let mut matches = Vec::new();
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let origin = Coordinates::new(x, y);
+                for (transform, stencil) in variants.iter() {
+                    if stencil.matches_at(grid, origin) {
+                        matches.push(StencilMatch {
+                            origin,
+                            transform: *transform,
+                        });
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+
+    pub fn count_matches(&self, grid: &Grid<char>, transforms: &[Transform]) -> usize {
+        self.find_matches(grid, transforms).len()
+    }
+}
+
+impl Transform {
+    pub const ALL: [Self; 8] = [
+        Self::Identity,
+        Self::Rotate90,
+        Self::Rotate180,
+        Self::Rotate270,
+        Self::FlipHorizontal,
+        Self::FlipVertical,
+        Self::Transpose,
+        Self::AntiTranspose,
+    ];
+
+    pub const ROTATIONS: [Self; 4] = [
+        Self::Identity,
+        Self::Rotate90,
+        Self::Rotate180,
+        Self::Rotate270,
+    ];
+
+    /// Width and height of a `width` by `height` stencil once transformed.
+    pub const fn dimensions(&self, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            Self::Identity | Self::Rotate180 | Self::FlipHorizontal | Self::FlipVertical => {
+                (width, height)
+            }
+            Self::Rotate90 | Self::Rotate270 | Self::Transpose | Self::AntiTranspose => {
+                (height, width)
+            }
+        }
+    }
+
+    /// Where the cell at `x`, `y` of a `width` by `height` stencil ends up.
+    pub const fn apply(&self, x: u32, y: u32, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            Self::Identity => (x, y),
+            Self::Rotate90 => (height - 1 - y, x),
+            Self::Rotate180 => (width - 1 - x, height - 1 - y),
+            Self::Rotate270 => (y, width - 1 - x),
+            Self::FlipHorizontal => (width - 1 - x, y),
+            Self::FlipVertical => (x, height - 1 - y),
+            Self::Transpose => (y, x),
+            Self::AntiTranspose => (height - 1 - y, width - 1 - x),
+        }
+    }
+}
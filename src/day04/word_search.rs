@@ -0,0 +1,187 @@
+use aho_corasick::{AhoCorasick, MatchKind};
+use aoc_utils::{
+    grid::{Coordinates, Grid},
+    AocError,
+};
+
+use super::Direction;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Report matches sharing letters on the same line, like `XMAS` and `SAMX` in `XMASAMX`
+    pub overlapping: bool,
+    /// Report a palindromic word once per match rather than once per reading direction
+    pub dedup_palindromes: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            overlapping: true,
+            dedup_palindromes: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordMatch<'a> {
+    /// Coordinates of the word's first letter
+    pub start: Coordinates,
+    pub direction: Direction,
+    pub word: &'a str,
+}
+
+/// Finds the words of a dictionary in a grid, in all eight directions.
+///
+/// Every row, column and diagonal of the grid is scanned once, by an automaton looking for the
+/// words and their reverses, which are the words read in the opposite direction.
+#[derive(Debug, Clone)]
+pub struct WordSearch {
+    words: Vec<String>,
+    patterns: Vec<Pattern>,
+    automaton: AhoCorasick,
+    options: SearchOptions,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pattern {
+    word: usize,
+    reversed: bool,
+    palindrome: bool,
+}
+
+impl WordSearch {
+    pub fn new<I, S>(words: I, options: SearchOptions) -> anyhow::Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let words = words.into_iter().map(Into::into).collect::<Vec<String>>();
+
+        let mut patterns = Vec::new();
+        let mut texts = Vec::new();
+        for (index, word) in words.iter().enumerate() {
+            // A single letter has no direction to read it in
+            if word.chars().count() < 2 {
+                return Err(AocError::message(format!(
+                    "Word '{word}' must have at least two letters"
+                ))
+                .into());
+            }
+
+            let reverse = word.chars().rev().collect::<String>();
+            let palindrome = reverse == *word;
+
+            patterns.push(Pattern {
+                word: index,
+                reversed: false,
+                palindrome,
+            });
+            texts.push(word.clone());
+
+            if !palindrome {
+                patterns.push(Pattern {
+                    word: index,
+                    reversed: true,
+                    palindrome,
+                });
+                texts.push(reverse);
+            }
+        }
+
+        let match_kind = if options.overlapping {
+            MatchKind::Standard
+        } else {
+            MatchKind::LeftmostLongest
+        };
+        let automaton = AhoCorasick::builder().match_kind(match_kind).build(texts)?;
+
+        Ok(Self {
+            words,
+            patterns,
+            automaton,
+            options,
+        })
+    }
+
+    /// Every match of the words in `grid`, line by line.
+    pub fn find_all(&self, grid: &Grid<char>) -> Vec<WordMatch<'_>> {
+        let mut matches = Vec::new();
+        for (direction, cells) in lines(grid) {
+            let line = cells.iter().map(|&c| grid[c]).collect::<String>();
+            let offsets = line.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+            let char_index = |byte: usize| offsets.binary_search(&byte).unwrap_or(cells.len());
+
+            let mut add = |start: usize, end: usize, pattern: Pattern| {
+                let word = self.words[pattern.word].as_str();
+                let (first, last) = (cells[char_index(start)], cells[char_index(end) - 1]);
+
+                if pattern.reversed {
+                    matches.push(WordMatch {
+                        start: last,
+                        direction: direction.opposite(),
+                        word,
+                    });
+                } else {
+                    matches.push(WordMatch {
+                        start: first,
+                        direction,
+                        word,
+                    });
+
+                    if pattern.palindrome && !self.options.dedup_palindromes {
+                        matches.push(WordMatch {
+                            start: last,
+                            direction: direction.opposite(),
+                            word,
+                        });
+                    }
+                }
+            };
+
+            if self.options.overlapping {
+                for m in self.automaton.find_overlapping_iter(&line) {
+                    add(m.start(), m.end(), self.patterns[m.pattern()]);
+                }
+            } else {
+                for m in self.automaton.find_iter(&line) {
+                    add(m.start(), m.end(), self.patterns[m.pattern()]);
+                }
+            }
+        }
+
+        matches
+    }
+}
+
+/// The rows, columns and both diagonals of `grid`, with the direction they go in.
+fn lines(grid: &Grid<char>) -> Vec<(Direction, Vec<Coordinates>)> {
+    let (width, height) = (grid.width(), grid.height());
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    let walk = |start: Coordinates, direction: Direction| {
+        let cells = std::iter::successors(Some(start), |&c| direction.offset(c))
+            .take_while(|&c| c.x < width && c.y < height)
+            .collect::<Vec<_>>();
+        (direction, cells)
+    };
+
+    let mut lines = Vec::new();
+    lines.extend((0..height).map(|y| walk(Coordinates::new(0, y), Direction::East)));
+    lines.extend((0..width).map(|x| walk(Coordinates::new(x, 0), Direction::South)));
+
+    // Diagonals start along the top edge and down the side they point away from
+    let top = (0..width).map(|x| Coordinates::new(x, 0));
+    let left = (1..height).map(|y| Coordinates::new(0, y));
+    let right = (1..height).map(|y| Coordinates::new(width - 1, y));
+    lines.extend(
+        top.clone()
+            .chain(left)
+            .map(|c| walk(c, Direction::SouthEast)),
+    );
+    lines.extend(top.chain(right).map(|c| walk(c, Direction::SouthWest)));
+
+    lines
+}
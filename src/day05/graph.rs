@@ -0,0 +1,172 @@
+use aoc_utils::{
+    hashbrown::HashSet,
+    petgraph::{
+        algo::{
+            tarjan_scc,
+            tred::{dag_to_toposorted_adjacency_list, dag_transitive_reduction_closure},
+        },
+        graph::{DiGraph, NodeIndex},
+        visit::IntoNeighbors,
+    },
+};
+use itertools::Itertools;
+
+use super::{
+    ordering::{partial_order, topological_order, OrderError},
+    Rules,
+};
+
+/// The rules between a set of pages, as a graph with an edge from each page to the pages it must
+/// come before.
+pub struct RuleGraph {
+    pages: Vec<i64>,
+    rules: Rules,
+}
+
+impl RuleGraph {
+    /// The graph of the rules between `pages`.
+    pub fn new(rules: &Rules, pages: &[i64]) -> Self {
+        let included = pages.iter().copied().collect::<HashSet<_>>();
+
+        let mut restricted = Rules::new();
+        for (first, second) in rules.iter() {
+            if included.contains(&first) && included.contains(&second) {
+                restricted.insert(first, second);
+            }
+        }
+
+        Self {
+            pages: pages.iter().copied().unique().collect(),
+            rules: restricted,
+        }
+    }
+
+    /// The graph of every rule, over all the pages they mention.
+    pub fn from_rules(rules: &Rules) -> Self {
+        let pages = rules
+            .iter()
+            .flat_map(|(first, second)| [first, second])
+            .sorted()
+            .dedup()
+            .collect::<Vec<_>>();
+        Self::new(rules, &pages)
+    }
+
+    /// The edges of the graph, sorted.
+    pub fn rules(&self) -> Vec<(i64, i64)> {
+        self.rules.iter().sorted().collect()
+    }
+
+    /// The strongly connected components of more than one page, or of a page ordered before
+    /// itself, sorted. Each of them is a set of rules that can't all be followed.
+    pub fn cycles(&self) -> Vec<Vec<i64>> {
+        let (graph, _) = self.to_graph(&self.pages);
+        tarjan_scc(&graph)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || {
+                    let page = graph[component[0]];
+                    self.rules.contains(page, page)
+                }
+            })
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|node| graph[node])
+                    .sorted()
+                    .collect()
+            })
+            .sorted()
+            .collect()
+    }
+
+    /// One order of all the pages following every rule, if the rules are consistent.
+    pub fn order(&self) -> Result<Vec<i64>, OrderError> {
+        partial_order(&self.rules, &self.pages)
+    }
+
+    /// The only order of all the pages following every rule, if the rules are consistent and
+    /// order every pair of pages.
+    pub fn unique_order(&self) -> Result<Vec<i64>, OrderError> {
+        topological_order(&self.rules, &self.pages)
+    }
+
+    /// The graph with only the rules not implied by others, which order the pages the same way.
+    pub fn transitive_reduction(&self) -> Result<Self, OrderError> {
+        let order = self.order()?;
+
+        // Nodes are added in topological order
+        let (graph, nodes) = self.to_graph(&order);
+        let (list, _) = dag_to_toposorted_adjacency_list::<_, u32>(&graph, &nodes);
+        let (reduction, _) = dag_transitive_reduction_closure(&list);
+
+        let mut rules = Rules::new();
+        for first in reduction.node_indices() {
+            for second in reduction.neighbors(first) {
+                rules.insert(order[first as usize], order[second as usize]);
+            }
+        }
+
+        Ok(Self {
+            pages: self.pages.clone(),
+            rules,
+        })
+    }
+
+    /// The graph in Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph rules {\n");
+        self.write_dot(&mut dot, "", "    ");
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Several graphs in Graphviz DOT format, each in a cluster with its label.
+    pub fn clusters_to_dot<'a, I>(graphs: I) -> String
+    where
+        I: IntoIterator<Item = (String, &'a RuleGraph)>,
+    {
+        let mut dot = String::from("digraph rules {\n");
+        for (index, (label, graph)) in graphs.into_iter().enumerate() {
+            dot.push_str(&format!("    subgraph cluster_{index} {{\n"));
+            dot.push_str(&format!("        label=\"{label}\";\n"));
+            graph.write_dot(&mut dot, &format!("{index}:"), "        ");
+            dot.push_str("    }\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Writes the nodes and edges of the graph, with node ids starting with `prefix` so graphs
+    /// can share a file.
+    fn write_dot(&self, dot: &mut String, prefix: &str, indent: &str) {
+        for page in self.pages.iter() {
+            dot.push_str(&format!("{indent}\"{prefix}{page}\" [label=\"{page}\"];\n"));
+        }
+
+        for (first, second) in self.rules() {
+            dot.push_str(&format!(
+                "{indent}\"{prefix}{first}\" -> \"{prefix}{second}\";\n"
+            ));
+        }
+    }
+
+    /// Builds a petgraph graph with the nodes of `pages` in order.
+    fn to_graph(&self, pages: &[i64]) -> (DiGraph<i64, ()>, Vec<NodeIndex>) {
+        let mut graph = DiGraph::new();
+        let nodes = pages
+            .iter()
+            .map(|&page| graph.add_node(page))
+            .collect::<Vec<_>>();
+
+        for (a, &first) in pages.iter().enumerate() {
+            for (b, &second) in pages.iter().enumerate() {
+                if self.rules.contains(first, second) {
+                    graph.add_edge(nodes[a], nodes[b], ());
+                }
+            }
+        }
+
+        (graph, nodes)
+    }
+}
@@ -1,5 +1,16 @@
 use aoc_utils::{hashbrown::HashSet, str::StrExt, AocError};
 use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{dump, generate::Rng, input};
+
+use self::{
+    graph::RuleGraph,
+    ordering::{topological_order, OrderError},
+};
+
+mod graph;
+mod ordering;
 
 const INPUT: &str = include_str!("input.txt");
 
@@ -7,12 +18,13 @@ pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<i64> {
     let (rules, updates) = parse(input)?;
+    dump_rule_graphs(&rules, &updates);
 
     let mut total = 0;
     for update in updates {
@@ -33,7 +45,7 @@ fn part_2(input: &str) -> anyhow::Result<i64> {
             continue;
         }
 
-        fix_update(&rules, &mut update.pages);
+        update.pages = topological_order(&rules, &update.pages)?;
 
         total += update.middle();
     }
@@ -48,16 +60,54 @@ fn is_valid_update(rules: &Rules, update: &[i64]) -> bool {
         .all(|(&first, &second)| rules.is_valid_order(first, second))
 }
 
-fn fix_update(rules: &Rules, update: &mut [i64]) {
-    update.sort_by(|&first, &second| {
-        if rules.is_valid_order(first, second) {
-            std::cmp::Ordering::Less
-        } else {
-            std::cmp::Ordering::Greater
-        }
+/// Dumps an analysis of the whole rule set, and the rules that constrain each update.
+fn dump_rule_graphs(rules: &Rules, updates: &[Update]) {
+    dump::artifact("day05-analysis", || analyze_rules(rules));
+
+    // Cyclic rules have no reduction, so they are drawn as they are
+    dump::text("day05-rules.dot", || {
+        let graph = RuleGraph::from_rules(rules);
+        graph.transitive_reduction().unwrap_or(graph).to_dot()
+    });
+
+    dump::text("day05-updates.dot", || {
+        let graphs = updates
+            .iter()
+            .map(|update| {
+                let graph = RuleGraph::new(rules, &update.pages);
+                graph.transitive_reduction().unwrap_or(graph)
+            })
+            .collect::<Vec<_>>();
+        let labels = updates.iter().map(|update| {
+            let pages = update.pages.iter().join(",");
+            if is_valid_update(rules, &update.pages) {
+                pages
+            } else {
+                format!("{pages} (out of order)")
+            }
+        });
+        RuleGraph::clusters_to_dot(labels.zip(graphs.iter()))
     });
 }
 
+fn analyze_rules(rules: &Rules) -> AnalysisDump {
+    let graph = RuleGraph::from_rules(rules);
+    let order = graph.order();
+    let ambiguous_pages = match graph.unique_order() {
+        Err(OrderError::Ambiguous(first, second)) => Some((first, second)),
+        _ => None,
+    };
+
+    AnalysisDump {
+        cycles: graph.cycles(),
+        order_is_unique: order.is_ok() && ambiguous_pages.is_none(),
+        ambiguous_pages,
+        order: order.as_ref().ok().cloned(),
+        order_error: order.err().map(|e| e.to_string()),
+        reduced_rules: graph.transitive_reduction().ok().map(|graph| graph.rules()),
+    }
+}
+
 fn parse(input: &str) -> anyhow::Result<(Rules, Vec<Update>)> {
     let mut paragraphs = input.paragraphs();
     let rules_input = paragraphs.next().ok_or(AocError::InvalidInput)?;
@@ -95,12 +145,22 @@ impl Rules {
     }
 
     pub fn is_valid_order(&self, first: i64, second: i64) -> bool {
-        !self.rules.contains(&(second, first))
+        !self.contains(second, first)
+    }
+
+    /// Whether a rule puts `first` before `second`.
+    pub fn contains(&self, first: i64, second: i64) -> bool {
+        self.rules.contains(&(first, second))
     }
 
     pub fn insert(&mut self, first: i64, second: i64) {
         self.rules.insert((first, second));
     }
+
+    /// Every rule as a pair of pages, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.rules.iter().copied()
+    }
 }
 
 struct Update {
@@ -114,9 +174,71 @@ impl Update {
     }
 }
 
+#[derive(Debug, Serialize)]
+struct AnalysisDump {
+    /// Sets of pages whose rules contradict each other
+    cycles: Vec<Vec<i64>>,
+    /// One order of the pages following every rule, if there are no cycles
+    order: Option<Vec<i64>>,
+    /// Whether `order` is the only one, as the rules order every pair of pages
+    order_is_unique: bool,
+    /// The first two pages the rules leave unordered, if any
+    ambiguous_pages: Option<(i64, i64)>,
+    order_error: Option<String>,
+    /// The rules not implied by other rules, if there are no cycles
+    reduced_rules: Option<Vec<(i64, i64)>>,
+}
+
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates rules ordering every pair of 49 pages, followed by `size` updates of which about
+/// half are already correctly ordered.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut pages = (10..100).collect::<Vec<i64>>();
+    rng.shuffle(&mut pages);
+    pages.truncate(49);
+
+    let mut rules = Vec::new();
+    for (index, &first) in pages.iter().enumerate() {
+        for &second in pages[index + 1..].iter() {
+            rules.push((first, second));
+        }
+    }
+
+    rng.shuffle(&mut rules);
+
+    let mut output = String::new();
+    for (first, second) in rules {
+        output.push_str(&format!("{first}|{second}\n"));
+    }
+
+    output.push('\n');
+
+    for _ in 0..size.max(1) {
+        let len = 2 * rng.range(2..=11) as usize + 1;
+        let mut update = pages.clone();
+        rng.shuffle(&mut update);
+        update.truncate(len);
+
+        if rng.chance(0.5) {
+            update.sort_by_key(|page| pages.iter().position(|other| other == page));
+        }
+
+        output.push_str(&update.iter().join(","));
+        output.push('\n');
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ordering::OrderError;
+    use rstest::*;
 
     const EXAMPLE_1: &str = include_str!("example.1.txt");
 
@@ -132,4 +254,113 @@ mod tests {
         let result = part_2(EXAMPLE_1).unwrap();
         assert_eq!(result, 123);
     }
+
+    #[rstest]
+    #[case(&[(1, 2), (2, 3), (1, 3)], &[3, 1, 2], Ok(vec![1, 2, 3]))]
+    #[case(&[(1, 2), (2, 3)], &[3, 2, 1], Ok(vec![1, 2, 3]))]
+    #[case(&[(1, 2), (1, 3)], &[3, 2, 1], Err(OrderError::Ambiguous(3, 2)))]
+    #[case(&[(1, 2), (2, 3), (3, 1)], &[1, 2, 3], Err(OrderError::Cycle(vec![(1, 2), (2, 3), (3, 1)])))]
+    #[case(&[(4, 1), (1, 2), (2, 3), (3, 2)], &[1, 2, 3, 4], Err(OrderError::Cycle(vec![(2, 3), (3, 2)])))]
+    fn test_topological_order(
+        #[case] pairs: &[(i64, i64)],
+        #[case] pages: &[i64],
+        #[case] expected: Result<Vec<i64>, OrderError>,
+    ) {
+        let mut rules = Rules::new();
+        for &(first, second) in pairs {
+            rules.insert(first, second);
+        }
+
+        assert_eq!(topological_order(&rules, pages), expected);
+    }
+
+    #[test]
+    fn test_cycle_message() {
+        let input = "1|2\n2|3\n3|1\n\n1,2,3\n";
+        let error = part_2(input).unwrap_err();
+        assert_eq!(error.to_string(), "Rules form a cycle: 1|2, 2|3, 3|1");
+    }
+
+    #[test]
+    fn test_rule_graph() {
+        let (rules, _) = parse(EXAMPLE_1).unwrap();
+        let graph = RuleGraph::from_rules(&rules);
+        assert!(graph.cycles().is_empty());
+
+        let order = graph.order().unwrap();
+        assert_eq!(order, [97, 75, 47, 61, 53, 29, 13]);
+
+        let reduction = graph.transitive_reduction().unwrap();
+        let chain = order.iter().copied().tuple_windows().collect::<Vec<_>>();
+        assert_eq!(reduction.rules().len(), chain.len());
+        assert!(chain.iter().all(|pair| reduction.rules().contains(pair)));
+        assert_eq!(reduction.order().unwrap(), order);
+
+        let update = RuleGraph::new(&rules, &[75, 97, 47, 61, 53]);
+        assert_eq!(update.rules().len(), 10);
+        assert_eq!(
+            update.transitive_reduction().unwrap().rules(),
+            [(47, 61), (61, 53), (75, 47), (97, 75)]
+        );
+    }
+
+    #[test]
+    fn test_analyze_rules() {
+        let (rules, _) = parse(EXAMPLE_1).unwrap();
+        let analysis = analyze_rules(&rules);
+        assert!(analysis.order_is_unique);
+        assert_eq!(analysis.ambiguous_pages, None);
+
+        let (rules, _) = parse("1|2\n1|3\n\n1,2\n").unwrap();
+        let analysis = analyze_rules(&rules);
+        assert!(analysis.order.is_some());
+        assert!(!analysis.order_is_unique);
+        assert_eq!(analysis.ambiguous_pages, Some((2, 3)));
+
+        let (rules, _) = parse("1|2\n2|1\n\n1,2\n").unwrap();
+        let analysis = analyze_rules(&rules);
+        assert!(!analysis.order_is_unique);
+        assert!(analysis.order_error.is_some());
+    }
+
+    #[test]
+    fn test_rule_graph_cycles() {
+        let input = "1|2\n2|3\n3|1\n3|4\n4|6\n5|5\n\n1,2\n";
+        let (rules, _) = parse(input).unwrap();
+        let graph = RuleGraph::from_rules(&rules);
+
+        assert_eq!(graph.rules().len(), 6);
+        assert_eq!(graph.cycles(), [vec![1, 2, 3], vec![5]]);
+        assert!(matches!(graph.order(), Err(OrderError::Cycle(_))));
+        assert!(graph.transitive_reduction().is_err());
+
+        let acyclic = RuleGraph::new(&rules, &[3, 4, 6]);
+        assert!(acyclic.cycles().is_empty());
+        assert_eq!(acyclic.order().unwrap(), [3, 4, 6]);
+    }
+
+    #[test]
+    fn test_rule_graph_dot() {
+        let (rules, _) = parse("1|2\n2|3\n1|3\n\n1,2\n").unwrap();
+        let graph = RuleGraph::new(&rules, &[1, 3]);
+        assert_eq!(
+            graph.to_dot(),
+            "digraph rules {\n    \"1\" [label=\"1\"];\n    \"3\" [label=\"3\"];\n    \"1\" -> \"3\";\n}\n"
+        );
+
+        let reduction = RuleGraph::from_rules(&rules)
+            .transitive_reduction()
+            .unwrap();
+        let dot = RuleGraph::clusters_to_dot([("all".to_string(), &reduction)]);
+        assert!(dot.contains("subgraph cluster_0 {\n        label=\"all\";\n"));
+        assert!(dot.contains("\"0:1\" -> \"0:2\";"));
+        assert!(!dot.contains("\"0:1\" -> \"0:3\";"));
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
@@ -0,0 +1,116 @@
+use itertools::Itertools;
+
+use super::Rules;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    #[error("Rules form a cycle: {}", format_rules(.0))]
+    Cycle(Vec<(i64, i64)>),
+    #[error("No rule orders pages {0} and {1}")]
+    Ambiguous(i64, i64),
+}
+
+fn format_rules(rules: &[(i64, i64)]) -> String {
+    rules
+        .iter()
+        .map(|(first, second)| format!("{first}|{second}"))
+        .join(", ")
+}
+
+/// Orders `pages` by the rules between them, with a topological sort.
+///
+/// Fails if the rules contradict each other, or leave more than one possible order.
+pub fn topological_order(rules: &Rules, pages: &[i64]) -> Result<Vec<i64>, OrderError> {
+    let Sort { order, ambiguity } = sort(rules, pages)?;
+    match ambiguity {
+        Some((first, second)) => Err(OrderError::Ambiguous(first, second)),
+        None => Ok(order),
+    }
+}
+
+/// Orders `pages` by the rules between them, picking one of the orders if there are several.
+///
+/// Only fails if the rules contradict each other.
+pub fn partial_order(rules: &Rules, pages: &[i64]) -> Result<Vec<i64>, OrderError> {
+    sort(rules, pages).map(|sort| sort.order)
+}
+
+struct Sort {
+    order: Vec<i64>,
+    /// The first two pages the rules leave unordered, if any
+    ambiguity: Option<(i64, i64)>,
+}
+
+fn sort(rules: &Rules, pages: &[i64]) -> Result<Sort, OrderError> {
+    let mut successors = vec![Vec::new(); pages.len()];
+    let mut in_degrees = vec![0; pages.len()];
+    for (a, &first) in pages.iter().enumerate() {
+        for (b, &second) in pages.iter().enumerate() {
+            if rules.contains(first, second) {
+                successors[a].push(b);
+                in_degrees[b] += 1;
+            }
+        }
+    }
+
+    let mut ready = (0..pages.len())
+        .filter(|&index| in_degrees[index] == 0)
+        .collect::<Vec<_>>();
+    let mut order = Vec::with_capacity(pages.len());
+    let mut ambiguity = None;
+    while let Some(index) = ready.pop() {
+        // Any of the pages ready could come next
+        if let Some(&other) = ready.last().filter(|_| ambiguity.is_none()) {
+            let (a, b) = (index.min(other), index.max(other));
+            ambiguity = Some((pages[a], pages[b]));
+        }
+
+        order.push(pages[index]);
+        for &next in successors[index].iter() {
+            in_degrees[next] -= 1;
+            if in_degrees[next] == 0 {
+                ready.push(next);
+            }
+        }
+    }
+
+    if order.len() < pages.len() {
+        let cycle = find_cycle(&successors, &in_degrees);
+        let rules = cycle
+            .into_iter()
+            .circular_tuple_windows()
+            .map(|(first, second)| (pages[first], pages[second]))
+            .collect();
+        return Err(OrderError::Cycle(rules));
+    }
+
+    Ok(Sort { order, ambiguity })
+}
+
+/// Finds a cycle among the pages left over by the sort, which all still have predecessors.
+fn find_cycle(successors: &[Vec<usize>], in_degrees: &[usize]) -> Vec<usize> {
+    let is_left = |index: usize| in_degrees[index] > 0;
+    let predecessor = |index: usize| {
+        (0..successors.len())
+            .find(|&other| is_left(other) && successors[other].contains(&index))
+            .expect("Pages left over should have a predecessor left over")
+    };
+
+    // Walk backwards until a page comes around again
+    let start = (0..successors.len())
+        .find(|&index| is_left(index))
+        .expect("Some pages should be left over");
+    let mut path = vec![start];
+    loop {
+        let previous = predecessor(*path.last().expect("Path should not be empty"));
+        if let Some(position) = path.iter().position(|&index| index == previous) {
+            // Start from the page that comes first in the update
+            let mut cycle = path[position..].iter().rev().copied().collect::<Vec<_>>();
+            let first = cycle.iter().position_min().unwrap_or(0);
+            cycle.rotate_left(first);
+            return cycle;
+        }
+
+        path.push(previous);
+    }
+}
@@ -1,29 +1,35 @@
 use aoc_utils::{
     direction::{Direction, Orientation},
-    grid::Coordinates,
+    grid::{Coordinates, Grid},
     hashbrown::{HashMap, HashSet},
     AocError,
 };
 use rayon::prelude::*;
 
+use crate::{animate::Animation, generate::Rng, input, progress::Progress, render};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<usize> {
     let (start_coordinates, lab) = parse(input)?;
-    let visited = visit_path(&lab, start_coordinates);
+    let visited = visit_path(&lab, start_coordinates, &mut Animation::new("day06"));
 
     Ok(visited.len())
 }
 
-fn visit_path(lab: &Lab, start_coordinates: Coordinates) -> HashSet<Coordinates> {
+fn visit_path(
+    lab: &Lab,
+    start_coordinates: Coordinates,
+    animation: &mut Animation,
+) -> HashSet<Coordinates> {
     let mut guard = Guard {
         position: start_coordinates,
         direction: Direction::Up,
@@ -31,6 +37,7 @@ fn visit_path(lab: &Lab, start_coordinates: Coordinates) -> HashSet<Coordinates>
 
     let mut visited = HashSet::new();
     visited.insert(guard.position);
+    animation.frame(|| lab_chars(lab, &visited, &guard), render::char_palette);
 
     loop {
         let result = step(lab, &guard);
@@ -39,6 +46,7 @@ fn visit_path(lab: &Lab, start_coordinates: Coordinates) -> HashSet<Coordinates>
         for _ in 0..distance {
             guard.apply_movement(1);
             visited.insert(guard.position);
+            animation.frame(|| lab_chars(lab, &visited, &guard), render::char_palette);
         }
 
         match result {
@@ -54,6 +62,28 @@ fn visit_path(lab: &Lab, start_coordinates: Coordinates) -> HashSet<Coordinates>
     visited
 }
 
+fn lab_chars(lab: &Lab, visited: &HashSet<Coordinates>, guard: &Guard) -> Grid<char> {
+    let mut chars = Grid::new_with(lab.size.x, lab.size.y, || '.');
+    for (&y, row) in lab.rows.iter() {
+        for &x in row {
+            chars[Coordinates::new(x, y)] = '#';
+        }
+    }
+
+    for &coordinates in visited {
+        chars[coordinates] = 'X';
+    }
+
+    chars[guard.position] = match guard.direction {
+        Direction::Up => '^',
+        Direction::Right => '>',
+        Direction::Down => 'v',
+        Direction::Left => '<',
+    };
+
+    chars
+}
+
 fn step(lab: &Lab, guard: &Guard) -> StepResult {
     match lab.find_obstacle(guard.position, guard.direction) {
         Ok((_, dist)) => StepResult::Obstacle(dist),
@@ -79,16 +109,19 @@ impl StepResult {
 
 fn part_2(input: &str) -> anyhow::Result<usize> {
     let (start_coordinates, lab) = parse(input)?;
-    let mut candidates = visit_path(&lab, start_coordinates);
+    let mut candidates = visit_path(&lab, start_coordinates, &mut Animation::disabled());
     candidates.remove(&start_coordinates);
     let candidates = Vec::from_iter(candidates);
 
+    let progress = Progress::new("day06", candidates.len() as u64);
     let count = candidates
         .par_iter()
         .filter(|&&candidate| {
             let mut lab = lab.clone();
             lab.insert(candidate);
-            find_loop(&lab, start_coordinates) == FindLoopResult::Loop
+            let result = find_loop(&lab, start_coordinates);
+            progress.inc();
+            result == FindLoopResult::Loop
         })
         .count();
 
@@ -290,6 +323,43 @@ enum FindObstacleError {
     NoObstacle(u32),
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a `size` by `size` lab, retrying until the guard's patrol leaves it.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2) as u32;
+
+    loop {
+        let start = Coordinates::new(rng.below(size as u64) as u32, rng.below(size as u64) as u32);
+
+        let mut lab = Lab::new();
+        lab.set_size(size, size);
+        let mut output = String::new();
+        for y in 0..size {
+            for x in 0..size {
+                let coordinates = Coordinates::new(x, y);
+                if coordinates == start {
+                    output.push('^');
+                } else if rng.chance(0.08) {
+                    lab.insert(coordinates);
+                    output.push('#');
+                } else {
+                    output.push('.');
+                }
+            }
+
+            output.push('\n');
+        }
+
+        if find_loop(&lab, start) == FindLoopResult::Exited {
+            return output;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -307,4 +377,11 @@ mod tests {
         let result = part_2(EXAMPLE_1).unwrap();
         assert_eq!(result, 6);
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
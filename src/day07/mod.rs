@@ -1,14 +1,17 @@
 use aoc_utils::AocError;
+use itertools::Itertools;
 use rayon::prelude::*;
 
+use crate::{generate::Rng, input};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<u64> {
@@ -117,6 +120,44 @@ impl Equation {
     }
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates `size` equations, about half of which can be made true.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    fn digits(value: u64) -> u32 {
+        value.checked_ilog10().unwrap_or(0) + 1
+    }
+
+    let mut output = String::new();
+    for _ in 0..size {
+        // Two-digit operands keep every intermediate value of the solvers far from overflowing
+        let count = rng.range(2..=8) as usize;
+        let operands = (0..count)
+            .map(|_| rng.range(1..=99) as u64)
+            .collect::<Vec<_>>();
+
+        let mut value = operands[0];
+        for &operand in operands[1..].iter() {
+            value = match rng.below(3) {
+                0 => value + operand,
+                1 => value * operand,
+                _ => value * 10u64.pow(digits(operand)) + operand,
+            };
+        }
+
+        if rng.chance(0.5) {
+            value += rng.range(1..=9) as u64;
+        }
+
+        output.push_str(&format!("{value}: {}\n", operands.iter().join(" ")));
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,4 +176,11 @@ mod tests {
         let result = part_2(EXAMPLE_1).unwrap();
         assert_eq!(result, 11387);
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
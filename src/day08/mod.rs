@@ -4,19 +4,21 @@ use aoc_utils::{
 };
 use itertools::Itertools;
 
+use crate::{generate::Rng, parsed::ParseStage};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder
+        .parse_input(INPUT, parse)
+        .add_part(part_1)
+        .add_part(part_2);
 }
 
-fn part_1(input: &str) -> anyhow::Result<usize> {
-    let map = parse(input)?;
-
+fn part_1(map: &Map) -> anyhow::Result<usize> {
     let mut antinodes = HashSet::new();
     for frequency in map.frequencies_iter() {
         for (&a, &b) in map.antennas(frequency).iter().tuple_combinations() {
@@ -37,9 +39,7 @@ fn part_1(input: &str) -> anyhow::Result<usize> {
     Ok(antinodes.len())
 }
 
-fn part_2(input: &str) -> anyhow::Result<usize> {
-    let map = parse(input)?;
-
+fn part_2(map: &Map) -> anyhow::Result<usize> {
     let mut antinodes = HashSet::new();
     for frequency in map.frequencies_iter() {
         for (&a, &b) in map.antennas(frequency).iter().tuple_combinations() {
@@ -130,6 +130,39 @@ impl Map {
     }
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a `size` by `size` map holding about `size` antennas, in groups sharing a
+/// frequency.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const FREQUENCIES: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    let size = size.max(2);
+    let mut grid = vec![vec!['.'; size]; size];
+    let mut placed = 0;
+    while placed < size {
+        let frequency = *rng.choose(FREQUENCIES) as char;
+        for _ in 0..rng.range(2..=4) {
+            let (x, y) = (rng.index(size), rng.index(size));
+            if grid[y][x] == '.' {
+                grid[y][x] = frequency;
+                placed += 1;
+            }
+        }
+    }
+
+    let mut output = String::new();
+    for row in grid {
+        output.extend(row);
+        output.push('\n');
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -138,13 +171,21 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let result = part_1(EXAMPLE_1).unwrap();
+        let result = part_1(&parse(EXAMPLE_1).unwrap()).unwrap();
         assert_eq!(result, 14);
     }
 
     #[test]
     fn test_part_2() {
-        let result = part_2(EXAMPLE_1).unwrap();
+        let result = part_2(&parse(EXAMPLE_1).unwrap()).unwrap();
         assert_eq!(result, 34);
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        let map = parse(&input).unwrap();
+        part_1(&map).unwrap();
+        part_2(&map).unwrap();
+    }
 }
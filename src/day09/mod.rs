@@ -1,13 +1,15 @@
 use aoc_utils::AocError;
 
+use crate::{generate::Rng, input};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<u64> {
@@ -102,7 +104,11 @@ fn parse(input: &str) -> anyhow::Result<Disk> {
         let len = c.to_digit(10).ok_or(AocError::InvalidInput)? as u64;
 
         if len == 0 {
-            debug_assert!(!is_file);
+            // Only free space can be empty
+            if is_file {
+                return Err(AocError::InvalidInput.into());
+            }
+
             continue;
         }
 
@@ -233,6 +239,26 @@ impl std::fmt::Debug for Span {
     }
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a disk map of `size` files.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut output = String::new();
+    for index in 0..size.max(1) {
+        if index != 0 {
+            output.push_str(&rng.range(0..=9).to_string());
+        }
+
+        output.push_str(&rng.range(1..=9).to_string());
+    }
+
+    output.push('\n');
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,4 +282,11 @@ mod tests {
         let result = part_2(input).unwrap();
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
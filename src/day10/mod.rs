@@ -5,6 +5,9 @@ use aoc_utils::{
     neighbors::CardinalNeighbors,
     AocError,
 };
+use itertools::Itertools;
+
+use crate::{generate::Rng, parsed::ParseStage};
 
 const INPUT: &str = include_str!("input.txt");
 
@@ -12,13 +15,14 @@ pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder
+        .parse_input(INPUT, parse)
+        .add_part(part_1)
+        .add_part(part_2);
 }
 
-fn part_1(input: &str) -> anyhow::Result<u64> {
-    let map = parse(input)?;
-    let scores = trail_scores(&map);
+fn part_1(map: &Map) -> anyhow::Result<u64> {
+    let scores = trail_scores(map);
     let total_score = map
         .trailheads
         .iter()
@@ -59,9 +63,8 @@ fn trail_scores(map: &Map) -> Grid<u64> {
     scores
 }
 
-fn part_2(input: &str) -> anyhow::Result<u64> {
-    let map = parse(input)?;
-    let ratings = trail_ratings(&map);
+fn part_2(map: &Map) -> anyhow::Result<u64> {
+    let ratings = trail_ratings(map);
     let total_rating = map
         .trailheads
         .iter()
@@ -125,6 +128,10 @@ fn parse(input: &str) -> anyhow::Result<Map> {
     for (y, line) in input.lines().enumerate() {
         line_count += 1;
 
+        if line.chars().count() != line_length {
+            return Err(AocError::InvalidInput.into());
+        }
+
         for (x, c) in line.chars().enumerate() {
             let height = match c {
                 '.' => 100, // something unreachable
@@ -158,6 +165,55 @@ struct Map {
     trailpeaks: Vec<Coordinates>,
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a `size` by `size` topographic map of random terrain with `size` hiking trails
+/// laid over it.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const DIRECTIONS: [(i64, i64); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+    let size = size.max(4);
+    let mut grid = vec![vec![0; size]; size];
+    for row in grid.iter_mut() {
+        for height in row.iter_mut() {
+            *height = rng.range(0..=9);
+        }
+    }
+
+    for _ in 0..size {
+        let (mut x, mut y) = (rng.index(size), rng.index(size));
+        grid[y][x] = 0;
+
+        for height in 1..=9 {
+            let (dx, dy) = *rng.choose(&DIRECTIONS);
+            let (Some(next_x), Some(next_y)) = (
+                x.checked_add_signed(dx as isize),
+                y.checked_add_signed(dy as isize),
+            ) else {
+                break;
+            };
+
+            if next_x >= size || next_y >= size {
+                break;
+            }
+
+            (x, y) = (next_x, next_y);
+            grid[y][x] = height;
+        }
+    }
+
+    let mut output = String::new();
+    for row in grid {
+        output.push_str(&row.iter().join(""));
+        output.push('\n');
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -178,7 +234,7 @@ mod tests {
     #[case(EXAMPLE_4, 4)]
     #[case(EXAMPLE_5, 3)]
     fn test_part_1(#[case] input: &str, #[case] expected: u64) {
-        let result = part_1(input).unwrap();
+        let result = part_1(&parse(input).unwrap()).unwrap();
         assert_eq!(result, expected)
     }
 
@@ -189,7 +245,15 @@ mod tests {
     #[case(EXAMPLE_4, 13)]
     #[case(EXAMPLE_7, 227)]
     fn test_part_2(#[case] input: &str, #[case] expected: u64) {
-        let result = part_2(input).unwrap();
+        let result = part_2(&parse(input).unwrap()).unwrap();
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        let map = parse(&input).unwrap();
+        part_1(&map).unwrap();
+        part_2(&map).unwrap();
+    }
 }
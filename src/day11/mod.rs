@@ -1,13 +1,15 @@
 use aoc_utils::hashbrown::HashMap;
 
+use crate::{generate::Rng, input};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<u64> {
@@ -142,6 +144,23 @@ fn count_digits(mut value: u64) -> u64 {
     digits
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a line of `size` stones.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let stones = (0..size.max(1))
+        .map(|_| {
+            let digits = rng.range(1..=7) as u32;
+            rng.range(0..=10i64.pow(digits) - 1).to_string()
+        })
+        .collect::<Vec<_>>();
+
+    format!("{}\n", stones.join(" "))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,4 +177,11 @@ mod tests {
     fn test_part_2() {
         let _result = part_2(EXAMPLE_1).unwrap();
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
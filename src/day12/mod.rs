@@ -6,18 +6,26 @@ use aoc_utils::{
     AocError,
 };
 
+use serde::Serialize;
+
+use crate::{dump, generate::Rng, parsed::ParseStage};
+
+#[cfg(all(test, feature = "reference"))]
+mod reference;
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder
+        .parse_input(INPUT, parse)
+        .add_part(part_1)
+        .add_part(part_2);
 }
 
-fn part_1(input: &str) -> anyhow::Result<u64> {
-    let map = parse(input)?;
+fn part_1(map: &Map) -> anyhow::Result<u64> {
     let total_price = map
         .regions
         .iter()
@@ -26,8 +34,7 @@ fn part_1(input: &str) -> anyhow::Result<u64> {
     Ok(total_price)
 }
 
-fn part_2(input: &str) -> anyhow::Result<u64> {
-    let map = parse(input)?;
+fn part_2(map: &Map) -> anyhow::Result<u64> {
     let total_price = map
         .regions
         .iter()
@@ -49,6 +56,10 @@ fn parse(input: &str) -> anyhow::Result<Map> {
     for line in input.lines() {
         line_count += 1;
 
+        if line.chars().count() != line_length {
+            return Err(AocError::InvalidInput.into());
+        }
+
         let tiles = line.chars().map(Tile::new);
         data.extend(tiles);
     }
@@ -60,6 +71,7 @@ fn parse(input: &str) -> anyhow::Result<Map> {
     };
 
     map.compute_regions();
+    dump::artifact("day12-regions", || map.dump_regions());
 
     Ok(map)
 }
@@ -222,6 +234,32 @@ impl Map {
             state = next_state;
         }
     }
+
+    fn dump_regions(&self) -> std::vec::Vec<RegionDump> {
+        let mut regions = self
+            .regions
+            .iter()
+            .map(|region| RegionDump {
+                plant: ' ',
+                area: region.area,
+                perimeter: region.perimeter,
+                sides: region.corners,
+                cells: std::vec::Vec::new(),
+            })
+            .collect::<std::vec::Vec<_>>();
+
+        for (index, tile) in self.grid.iter().enumerate() {
+            let (Some(region), Some(coordinates)) = (tile.region, self.grid.get_coordinates(index))
+            else {
+                continue;
+            };
+
+            regions[region].plant = tile.plant;
+            regions[region].cells.push([coordinates.x, coordinates.y]);
+        }
+
+        regions
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -246,6 +284,55 @@ struct Region {
     corners: u64,
 }
 
+/// A region as written by `--dump`, with the sides counted for part 2
+#[derive(Debug, Serialize)]
+struct RegionDump {
+    plant: char,
+    area: u64,
+    perimeter: u64,
+    sides: u64,
+    cells: std::vec::Vec<[u32; 2]>,
+}
+
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a `size` by `size` garden of irregular regions grown around `size` random seeds.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let seeds = (0..size)
+        .map(|_| {
+            let plant = (b'A' + rng.below(26) as u8) as char;
+            (rng.index(size), rng.index(size), plant)
+        })
+        .collect::<Vec<_>>();
+
+    let mut output = String::new();
+    for y in 0..size {
+        for x in 0..size {
+            let plant = if rng.chance(0.03) {
+                (b'A' + rng.below(26) as u8) as char
+            } else {
+                seeds
+                    .iter()
+                    .min_by_key(|&&(seed_x, seed_y, _)| {
+                        seed_x.abs_diff(x) + seed_y.abs_diff(y) + rng.index(3)
+                    })
+                    .map(|&(_, _, plant)| plant)
+                    .unwrap()
+            };
+
+            output.push(plant);
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,7 +347,7 @@ mod tests {
     #[case(EXAMPLE_1, 772)]
     #[case(EXAMPLE_2, 1930)]
     fn test_part_1(#[case] input: &str, #[case] expected: u64) {
-        let result = part_1(input).unwrap();
+        let result = part_1(&parse(input).unwrap()).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -270,7 +357,15 @@ mod tests {
     #[case(EXAMPLE_3, 236)]
     #[case(EXAMPLE_4, 368)]
     fn test_part_2(#[case] input: &str, #[case] expected: u64) {
-        let result = part_2(input).unwrap();
+        let result = part_2(&parse(input).unwrap()).unwrap();
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        let map = parse(&input).unwrap();
+        part_1(&map).unwrap();
+        part_2(&map).unwrap();
+    }
 }
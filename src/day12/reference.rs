@@ -0,0 +1,127 @@
+use std::collections::VecDeque;
+
+use aoc_utils::hashbrown::HashSet;
+
+type Cell = (i64, i64);
+
+const DIRECTIONS: [Cell; 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+pub fn part_1(input: &str) -> u64 {
+    regions(input)
+        .iter()
+        .map(|region| region.len() as u64 * fences(region).len() as u64)
+        .sum()
+}
+
+pub fn part_2(input: &str) -> u64 {
+    regions(input)
+        .iter()
+        .map(|region| region.len() as u64 * sides(region))
+        .sum()
+}
+
+fn regions(input: &str) -> Vec<HashSet<Cell>> {
+    let plots = input
+        .lines()
+        .map(|line| line.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let plant = |(x, y): Cell| {
+        let row = plots.get(usize::try_from(y).ok()?)?;
+        row.get(usize::try_from(x).ok()?).copied()
+    };
+
+    let mut seen = HashSet::new();
+    let mut regions = Vec::new();
+    for (y, row) in plots.iter().enumerate() {
+        for x in 0..row.len() {
+            let start = (x as i64, y as i64);
+            if !seen.insert(start) {
+                continue;
+            }
+
+            let mut region = HashSet::<_>::from_iter([start]);
+            let mut queue = VecDeque::from([start]);
+            while let Some((x, y)) = queue.pop_front() {
+                for (dx, dy) in DIRECTIONS {
+                    let next = (x + dx, y + dy);
+                    if plant(next) == plant(start) && seen.insert(next) {
+                        region.insert(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            regions.push(region);
+        }
+    }
+
+    regions
+}
+
+/// Every fence segment, as the cell it borders and the direction it faces.
+fn fences(region: &HashSet<Cell>) -> HashSet<(Cell, Cell)> {
+    let mut fences = HashSet::new();
+    for &(x, y) in region {
+        for (dx, dy) in DIRECTIONS {
+            if !region.contains(&(x + dx, y + dy)) {
+                fences.insert(((x, y), (dx, dy)));
+            }
+        }
+    }
+
+    fences
+}
+
+/// Groups fence segments facing the same way along a straight line into sides.
+fn sides(region: &HashSet<Cell>) -> u64 {
+    let fences = fences(region);
+    let mut seen = HashSet::new();
+    let mut sides = 0;
+    for &fence in fences.iter() {
+        if !seen.insert(fence) {
+            continue;
+        }
+
+        sides += 1;
+
+        let mut queue = VecDeque::from([fence]);
+        while let Some(((x, y), (dx, dy))) = queue.pop_front() {
+            // The side runs perpendicular to the direction the fence faces
+            for (along_x, along_y) in [(dy, dx), (-dy, -dx)] {
+                let next = ((x + along_x, y + along_y), (dx, dy));
+                if fences.contains(&next) && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    sides
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+
+    #[test]
+    fn test_matches_reference() {
+        for seed in 0..50 {
+            let size = 1 + seed as usize % 12;
+            let input = super::super::generate(&mut Rng::new(seed), size);
+            let map = super::super::parse(&input).unwrap();
+
+            assert_eq!(
+                super::super::part_1(&map).unwrap(),
+                part_1(&input),
+                "part 1, seed {seed}"
+            );
+            assert_eq!(
+                super::super::part_2(&map).unwrap(),
+                part_2(&input),
+                "part 2, seed {seed}"
+            );
+        }
+    }
+}
@@ -11,14 +11,16 @@ use aoc_utils::{
 };
 use num::Rational64;
 
+use crate::{generate::Rng, input};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<i64> {
@@ -186,6 +188,36 @@ impl Matrix {
     }
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates `size` claw machines, about half of which can win their prize.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut machines = Vec::new();
+    for _ in 0..size.max(1) {
+        let a = (rng.range(10..=99), rng.range(10..=99));
+        let b = (rng.range(10..=99), rng.range(10..=99));
+        let prize = if rng.chance(0.5) {
+            let (a_presses, b_presses) = (rng.range(0..=100), rng.range(0..=100));
+            (
+                a_presses * a.0 + b_presses * b.0,
+                a_presses * a.1 + b_presses * b.1,
+            )
+        } else {
+            (rng.range(1000..=20000), rng.range(1000..=20000))
+        };
+
+        machines.push(format!(
+            "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+            a.0, a.1, b.0, b.1, prize.0, prize.1
+        ));
+    }
+
+    machines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,4 +236,11 @@ mod tests {
         aoc_utils::tracing::setup_tracing(crate::AOC_LOG);
         let _result = part_2(EXAMPLE_1).unwrap();
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
@@ -1,5 +1,5 @@
 use aoc_utils::{
-    grid::{bitmap::BrailleBitmap, Coordinates, Grid},
+    grid::{Coordinates, Grid},
     nalgebra,
     nom::{
         self,
@@ -11,22 +11,60 @@ use aoc_utils::{
     AocError,
 };
 
+use crate::{
+    animate::Animation,
+    generate::Rng,
+    input,
+    render::{self, Rgb},
+    variant::VariantBuilder,
+};
+
+#[cfg(all(test, feature = "reference"))]
+mod reference;
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT, 101, 103));
-    builder.add_part(|| part_2(INPUT, 101, 103));
+    builder.add_part(|| {
+        let (width, height) = room()?;
+        part_1(&input::get(INPUT), width, height)
+    });
+    builder.add_part(|| {
+        let (width, height) = room()?;
+        part_2(&input::get(INPUT), width, height)
+    });
+}
+
+pub fn variants(builder: &mut VariantBuilder) {
+    // Same as the registered part 2, named so it can be picked next to `scan`
+    builder.add_variant(2, "crt", || {
+        let (width, height) = room()?;
+        part_2(&input::get(INPUT), width, height)
+    });
+    builder.add_variant(2, "scan", || {
+        let (width, height) = room()?;
+        part_2_scan(&input::get(INPUT), width, height)
+    });
+}
+
+fn room() -> Result<(u64, u64), AocError> {
+    Ok((input::param("width", 101)?, input::param("height", 103)?))
 }
 
 fn part_1(input: &str, width: u64, height: u64) -> anyhow::Result<i64> {
-    let mut robots = parse(input)?;
+    let mut robots = parse(input, width, height)?;
+    let mut animation = Animation::new("day14");
+    animation.frame(|| robot_chars(&robots, width, height), robot_palette);
     for _ in 0..100 {
         step(&mut robots, width, height);
+        animation.frame(|| robot_chars(&robots, width, height), robot_palette);
     }
 
+    render_robots("day14-part1", &robots, width, height);
+
     let result = security_factor(&robots, width, height);
     Ok(result)
 }
@@ -62,7 +100,8 @@ fn security_factor(robots: &[Robot], width: u64, height: u64) -> i64 {
 }
 
 fn part_2(input: &str, width: u64, height: u64) -> anyhow::Result<i64> {
-    let mut robots = parse(input)?;
+    let mut robots = parse(input, width, height)?;
+    let initial_robots = robots.clone();
 
     let mut min_x_variance = (i64::MAX, 0);
     let mut min_y_variance = (i64::MAX, 0);
@@ -94,12 +133,41 @@ fn part_2(input: &str, width: u64, height: u64) -> anyhow::Result<i64> {
     // I blatantly stole this from the advent of code subreddit. Look at the previous `Day 14`
     // commits to see my actual solution.
     if let Some(result) = aoc_utils::numerics::crt(congruences) {
+        if render::is_enabled() {
+            let mut robots = initial_robots;
+            for robot in robots.iter_mut() {
+                robot.position += robot.velocity * result.remainder;
+                robot.position.x = robot.position.x.rem_euclid(width as i64);
+                robot.position.y = robot.position.y.rem_euclid(height as i64);
+            }
+
+            render_robots("day14-part2", &robots, width, height);
+        }
+
         Ok(result.remainder)
     } else {
         Err(AocError::message("Unable to find a tree").into())
     }
 }
 
+/// Steps through every second until the robots are back where they started, and picks the one
+/// where they are the most bunched up.
+fn part_2_scan(input: &str, width: u64, height: u64) -> anyhow::Result<i64> {
+    let mut robots = parse(input, width, height)?;
+
+    let mut best = (i64::MAX, 0);
+    for second in 0..(width * height) as i64 {
+        let variance = position_variance(&robots);
+        if variance.x + variance.y < best.0 {
+            best = (variance.x + variance.y, second);
+        }
+
+        step(&mut robots, width, height);
+    }
+
+    Ok(best.1)
+}
+
 fn position_variance(robots: &[Robot]) -> Vec2 {
     let mean = {
         let sum = robots.iter().map(|robot| robot.position).sum::<Vec2>();
@@ -114,8 +182,7 @@ fn position_variance(robots: &[Robot]) -> Vec2 {
     sum / robots.len() as i64
 }
 
-#[allow(dead_code)]
-fn print_map(robots: &[Robot], width: u64, height: u64) {
+fn robot_grid(robots: &[Robot], width: u64, height: u64) -> Grid<bool> {
     let mut grid = Grid::<bool>::new(width as u32, height as u32);
 
     for robot in robots {
@@ -123,11 +190,45 @@ fn print_map(robots: &[Robot], width: u64, height: u64) {
         grid[coordinates] = true;
     }
 
-    let bitmap = BrailleBitmap(grid);
-    println!("{bitmap}");
+    grid
+}
+
+fn render_robots(name: &str, robots: &[Robot], width: u64, height: u64) {
+    if render::is_enabled() {
+        let grid = robot_grid(robots, width, height);
+        render::snapshot(
+            name,
+            &grid,
+            |&robot: &bool| {
+                if robot {
+                    Rgb::GREEN
+                } else {
+                    Rgb::BLACK
+                }
+            },
+        );
+    }
 }
 
-fn parse(input: &str) -> anyhow::Result<Vec<Robot>> {
+fn robot_chars(robots: &[Robot], width: u64, height: u64) -> Grid<char> {
+    let mut chars = Grid::new_with(width as u32, height as u32, || '.');
+    for robot in robots {
+        chars[Coordinates::new(robot.position.x as u32, robot.position.y as u32)] = '#';
+    }
+
+    chars
+}
+
+fn robot_palette(&tile: &char) -> Rgb {
+    if tile == '#' {
+        Rgb::GREEN
+    } else {
+        Rgb::BLACK
+    }
+}
+
+/// Parses the robots, wrapping their starting positions into the `width` by `height` room.
+fn parse(input: &str, width: u64, height: u64) -> anyhow::Result<Vec<Robot>> {
     fn parse_robot(input: &str) -> anyhow::Result<Robot> {
         let position = preceded(
             tag("p="),
@@ -164,7 +265,9 @@ fn parse(input: &str) -> anyhow::Result<Vec<Robot>> {
 
     let mut robots = Vec::new();
     for line in input.lines() {
-        let robot = parse_robot(line)?;
+        let mut robot = parse_robot(line)?;
+        robot.position.x = robot.position.x.rem_euclid(width as i64);
+        robot.position.y = robot.position.y.rem_euclid(height as i64);
         robots.push(robot);
     }
 
@@ -179,6 +282,45 @@ struct Robot {
     velocity: Vec2,
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input, 101, 103);
+}
+
+/// Generates `size` robots for the runner's 101 by 103 room. Half of them gather into a
+/// square picture after a random number of seconds. Like in the real inputs the picture sits
+/// around the middle of the room, off-center pictures would throw off the variance `part_2`
+/// looks at.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let (width, height) = (101, 103);
+    let time = rng.range(0..=width * height - 1);
+    let side = ((size / 2) as f64).sqrt().ceil().clamp(1.0, 50.0) as i64;
+    let gathering = (size / 2).min((side * side) as usize);
+    let corner = Vec2::new(
+        (width - side) / 2 + rng.range(-10..=10),
+        (height - side) / 2 + rng.range(-10..=10),
+    );
+
+    let mut output = String::new();
+    for index in 0..size {
+        let velocity = Vec2::new(rng.range(-99..=99), rng.range(-99..=99));
+        let position = if index < gathering {
+            let offset = Vec2::new(index as i64 % side, index as i64 / side);
+            let position = corner + offset - velocity * time;
+            Vec2::new(position.x.rem_euclid(width), position.y.rem_euclid(height))
+        } else {
+            Vec2::new(rng.range(0..=width - 1), rng.range(0..=height - 1))
+        };
+
+        output.push_str(&format!(
+            "p={},{} v={},{}\n",
+            position.x, position.y, velocity.x, velocity.y
+        ));
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,4 +332,36 @@ mod tests {
         let result = part_1(EXAMPLE_1, 11, 7).unwrap();
         assert_eq!(result, 12);
     }
+
+    #[test]
+    fn test_positions_outside_room() {
+        let inside = "p=1,2 v=1,1\np=5,5 v=-2,3\n";
+        let outside = "p=12,-5 v=1,1\np=-6,19 v=-2,3\n";
+
+        assert_eq!(
+            part_1(outside, 11, 7).unwrap(),
+            part_1(inside, 11, 7).unwrap()
+        );
+        assert_eq!(
+            part_2_scan(outside, 11, 7).unwrap(),
+            part_2_scan(inside, 11, 7).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input, 101, 103).unwrap();
+        part_2(&input, 101, 103).unwrap();
+    }
+
+    #[test]
+    fn test_part_2_variants() {
+        for seed in 0..4 {
+            let input = generate(&mut Rng::new(seed), 500);
+            let crt = part_2(&input, 101, 103).unwrap();
+            let scan = part_2_scan(&input, 101, 103).unwrap();
+            assert_eq!(crt, scan, "seed {seed}");
+        }
+    }
 }
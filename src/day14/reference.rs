@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+
+type Robot = ((i64, i64), (i64, i64));
+
+pub fn part_1(input: &str, width: i64, height: i64) -> i64 {
+    let mut quadrants = [0; 4];
+    for ((x, y), (vx, vy)) in robots(input) {
+        let x = (x + 100 * vx).rem_euclid(width);
+        let y = (y + 100 * vy).rem_euclid(height);
+
+        let left = x < width / 2;
+        let right = x > width / 2;
+        let top = y < height / 2;
+        let bottom = y > height / 2;
+        match (left, right, top, bottom) {
+            (true, _, true, _) => quadrants[0] += 1,
+            (_, true, true, _) => quadrants[1] += 1,
+            (true, _, _, true) => quadrants[2] += 1,
+            (_, true, _, true) => quadrants[3] += 1,
+            _ => {}
+        }
+    }
+
+    quadrants.into_iter().product()
+}
+
+/// Simulates every second until the positions repeat, returning the first one where some robot
+/// is surrounded by robots on all eight sides. The pictures are filled in, while scattered
+/// robots practically never fill a 3 by 3 block.
+pub fn part_2(input: &str, width: i64, height: i64) -> i64 {
+    let robots = robots(input);
+
+    for second in 0..width * height {
+        let positions = robots
+            .iter()
+            .map(|&((x, y), (vx, vy))| {
+                let x = (x + second * vx).rem_euclid(width);
+                let y = (y + second * vy).rem_euclid(height);
+                (x, y)
+            })
+            .collect::<HashSet<_>>();
+
+        let is_filled = |&(x, y): &(i64, i64)| {
+            (-1..=1).all(|dy| (-1..=1).all(|dx| positions.contains(&(x + dx, y + dy))))
+        };
+        if positions.iter().any(is_filled) {
+            return second;
+        }
+    }
+
+    panic!("The robots never fill a 3 by 3 block");
+}
+
+fn robots(input: &str) -> Vec<Robot> {
+    let pair = |input: &str| {
+        let (x, y) = input[2..].split_once(',').unwrap();
+        (x.parse().unwrap(), y.parse().unwrap())
+    };
+
+    input
+        .lines()
+        .map(|line| {
+            let (position, velocity) = line.split_once(' ').unwrap();
+            (pair(position), pair(velocity))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+
+    #[test]
+    fn test_matches_reference() {
+        for seed in 0..10 {
+            let input = super::super::generate(&mut Rng::new(seed), 200);
+
+            assert_eq!(
+                super::super::part_1(&input, 101, 103).unwrap(),
+                part_1(&input, 101, 103),
+                "part 1, seed {seed}"
+            );
+            assert_eq!(
+                super::super::part_2(&input, 101, 103).unwrap(),
+                part_2(&input, 101, 103),
+                "part 2, seed {seed}"
+            );
+        }
+    }
+}
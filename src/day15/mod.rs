@@ -10,21 +10,34 @@ use aoc_utils::{
     AocError,
 };
 
+use crate::{animate::Animation, generate::Rng, input, render};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<u64> {
     let (mut map, mut robot, path) = parse(input)?;
 
+    let mut animation = Animation::new("day15-part1");
+    animation.frame(|| map_chars(&map.grid, robot), render::char_palette);
     for &direction in path.iter() {
         move_robot(&mut robot, &mut map, direction);
+        animation.frame(|| map_chars(&map.grid, robot), render::char_palette);
+    }
+
+    if render::is_enabled() {
+        render::snapshot(
+            "day15-part1",
+            &map_chars(&map.grid, robot),
+            render::char_palette,
+        );
     }
 
     let result = gps_sum(&map.crates);
@@ -62,8 +75,19 @@ fn part_2(input: &str) -> anyhow::Result<u64> {
     let mut map = WideMap::new(map);
     robot.x *= 2;
 
+    let mut animation = Animation::new("day15-part2");
+    animation.frame(|| map_chars(&map.grid, robot), render::char_palette);
     for &direction in path.iter() {
         wide_move_robot(&mut robot, &mut map, direction);
+        animation.frame(|| map_chars(&map.grid, robot), render::char_palette);
+    }
+
+    if render::is_enabled() {
+        render::snapshot(
+            "day15-part2",
+            &map_chars(&map.grid, robot),
+            render::char_palette,
+        );
     }
 
     let result = gps_sum(&map.crates);
@@ -84,24 +108,16 @@ fn wide_move_robot(robot: &mut Coordinates, map: &mut WideMap, direction: Direct
     }
 }
 
-#[allow(dead_code)]
-fn print_map<T: DisplayChar>(grid: &Grid<T>, robot: Coordinates) {
-    let mut map = String::with_capacity(grid.len());
+fn map_chars<T: DisplayChar>(grid: &Grid<T>, robot: Coordinates) -> Grid<char> {
+    let mut chars = Grid::new_with(grid.width(), grid.height(), || ' ');
     for (y, row) in grid.rows().enumerate() {
-        if y != 0 {
-            map.push_str("\r\n");
-        }
-
         for (x, tile) in row.iter().enumerate() {
-            if x == robot.x as usize && y == robot.y as usize {
-                map.push('@');
-            } else {
-                map.push(tile.display_char());
-            }
+            chars[Coordinates::new(x as u32, y as u32)] = tile.display_char();
         }
     }
 
-    println!("{map}");
+    chars[robot] = '@';
+    chars
 }
 
 fn parse(input: &str) -> anyhow::Result<(Map, Coordinates, Vec<Direction>)> {
@@ -126,6 +142,10 @@ fn parse_map(input: &str) -> anyhow::Result<(Map, Coordinates)> {
     for (y, line) in input.lines().enumerate() {
         lines += 1;
 
+        if line.chars().count() != line_length {
+            return Err(AocError::InvalidInput.into());
+        }
+
         for (x, c) in line.chars().enumerate() {
             let coordinates = Coordinates::new(x as u32, y as u32);
 
@@ -347,6 +367,55 @@ impl DisplayChar for WideTile {
     }
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a walled `size` by `size` warehouse followed by `20 * size` moves.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const MOVES: &[u8] = b"<>^v";
+
+    let size = size.max(3);
+    let robot = (
+        rng.range(1..=size as i64 - 2),
+        rng.range(1..=size as i64 - 2),
+    );
+
+    let mut output = String::new();
+    for y in 0..size as i64 {
+        for x in 0..size as i64 {
+            let is_border = x == 0 || y == 0 || x == size as i64 - 1 || y == size as i64 - 1;
+            let tile = if is_border {
+                '#'
+            } else if (x, y) == robot {
+                '@'
+            } else if rng.chance(0.1) {
+                '#'
+            } else if rng.chance(0.25) {
+                'O'
+            } else {
+                '.'
+            };
+
+            output.push(tile);
+        }
+
+        output.push('\n');
+    }
+
+    for index in 0..20 * size {
+        if index % 70 == 0 {
+            output.push('\n');
+        }
+
+        output.push(*rng.choose(MOVES) as char);
+    }
+
+    output.push('\n');
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -373,4 +442,11 @@ mod tests {
         let result = part_2(input).unwrap();
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
@@ -3,11 +3,18 @@ use aoc_utils::{
     direction::{Direction, Orientation},
     grid::{Coordinates, Grid},
     hashbrown::{HashMap, HashSet},
-    petgraph::{graph::NodeIndex, Graph, Undirected},
+    petgraph::{graph::NodeIndex, visit::EdgeRef, Graph, Undirected},
     AocError,
 };
-
-mod search;
+use serde::Serialize;
+
+use crate::{
+    dump,
+    generate::Rng,
+    parsed::ParseStage,
+    render,
+    search::{self, ResultSet},
+};
 
 const INPUT: &str = include_str!("input.txt");
 
@@ -15,33 +22,48 @@ pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder
+        .parse_input(INPUT, parse)
+        .add_part(part_1)
+        .add_part(part_2);
 }
 
-fn part_1(input: &str) -> anyhow::Result<u64> {
-    let map = parse(input)?;
-    let cost = find_cheapest_path_cost(&map).ok_or(AocError::message("Unable to find a path"))?;
+fn part_1(map: &Map) -> anyhow::Result<u64> {
+    let cost = find_cheapest_path_cost(map).ok_or(AocError::message("Unable to find a path"))?;
     Ok(cost)
 }
 
 fn find_cheapest_path_cost(map: &Map) -> Option<u64> {
-    let results = self::search::search(map);
+    let results = search_map(map);
 
     let horizontal_end_cost = results.cost(map.end.0);
     let vertical_end_cost = results.cost(map.end.1);
+
+    dump::artifact("day16-graph", || map.dump_graph());
+    dump::artifact("day16-best-path", || {
+        // Only ends that were reached can be the cheapest
+        let end = [
+            (map.end.0, horizontal_end_cost),
+            (map.end.1, vertical_end_cost),
+        ]
+        .into_iter()
+        .filter_map(|(node, cost)| Some((node, cost?)))
+        .min_by_key(|&(_, cost)| cost);
+        let path = end.and_then(|(node, _)| results.path(node));
+        map.dump_path(&results, &path.unwrap_or_default())
+    });
+
     horizontal_end_cost.and_then(|h| vertical_end_cost.map(|v| std::cmp::min(h, v)))
 }
 
-fn part_2(input: &str) -> anyhow::Result<u64> {
-    let map = parse(input)?;
+fn part_2(map: &Map) -> anyhow::Result<u64> {
     let count =
-        find_cheapest_path_cell_count(&map).ok_or(AocError::message("Unable to find a path"))?;
+        find_cheapest_path_cell_count(map).ok_or(AocError::message("Unable to find a path"))?;
     Ok(count)
 }
 
 fn find_cheapest_path_cell_count(map: &Map) -> Option<u64> {
-    let results = self::search::search(map);
+    let results = search_map(map);
 
     let horizontal_end_cost = results.cost(map.end.0);
     let vertical_end_cost = results.cost(map.end.1);
@@ -49,30 +71,69 @@ fn find_cheapest_path_cell_count(map: &Map) -> Option<u64> {
         .and_then(|h| vertical_end_cost.map(|v| if h < v { map.end.0 } else { map.end.1 }))?;
 
     let mut cells = HashSet::new();
-    let mut stack = Vec::new();
-    stack.extend(results.edges_to(node));
-    while let Some((node, edge)) = stack.pop() {
-        stack.extend(results.edges_to(node));
-
-        if let Some((a, b)) = map.graph.edge_endpoints(edge) {
-            let a = map.graph.node_weight(a).unwrap().coordinates();
-            let b = map.graph.node_weight(b).unwrap().coordinates();
-
-            if a.x == b.x {
-                for y in a.y..=b.y {
-                    cells.insert(Coordinates::new(a.x, y));
-                }
-            } else if a.y == b.y {
-                for x in a.x..=b.x {
-                    cells.insert(Coordinates::new(x, a.y));
-                }
+    for (a, b) in results.edges_on_paths(node) {
+        let a = map.graph.node_weight(a).unwrap().coordinates();
+        let b = map.graph.node_weight(b).unwrap().coordinates();
+        let (a, b) = (a.inf(&b), a.sup(&b));
+
+        if a.x == b.x {
+            for y in a.y..=b.y {
+                cells.insert(Coordinates::new(a.x, y));
+            }
+        } else if a.y == b.y {
+            for x in a.x..=b.x {
+                cells.insert(Coordinates::new(x, a.y));
             }
         }
     }
 
+    if render::is_enabled() {
+        render::snapshot(
+            "day16-part2",
+            &path_chars(map, &cells),
+            render::char_palette,
+        );
+    }
+
     Some(cells.len() as u64)
 }
 
+fn path_chars(map: &Map, cells: &HashSet<Coordinates>) -> Grid<char> {
+    let mut chars = Grid::new_with(map.grid.width(), map.grid.height(), || '.');
+    for y in 0..map.grid.height() {
+        for x in 0..map.grid.width() {
+            let coordinates = Coordinates::new(x, y);
+            if matches!(map.grid.get(coordinates), Some(Tile::Wall)) {
+                chars[coordinates] = '#';
+            } else if cells.contains(&coordinates) {
+                chars[coordinates] = 'O';
+            }
+        }
+    }
+
+    for node in [map.start, map.end.0] {
+        if let Some(weight) = map.graph.node_weight(node) {
+            chars[weight.coordinates()] = if node == map.start { 'S' } else { 'E' };
+        }
+    }
+
+    chars
+}
+
+fn search_map(map: &Map) -> ResultSet<NodeIndex, u64> {
+    search::dijkstra(map.start, |node| {
+        map.graph.edges(node).map(move |edge| {
+            let neighbor = if edge.source() == node {
+                edge.target()
+            } else {
+                edge.source()
+            };
+
+            (neighbor, edge.weight().cost())
+        })
+    })
+}
+
 fn parse(input: &str) -> anyhow::Result<Map> {
     let line_length = input
         .lines()
@@ -88,6 +149,10 @@ fn parse(input: &str) -> anyhow::Result<Map> {
     for (y, line) in input.lines().enumerate() {
         lines += 1;
 
+        if line.chars().count() != line_length {
+            return Err(AocError::InvalidInput.into());
+        }
+
         for (x, c) in line.chars().enumerate() {
             let coordinates = Coordinates::new(x as u32, y as u32);
 
@@ -122,6 +187,7 @@ enum Tile {
 }
 
 struct Map {
+    grid: Grid<Tile>,
     graph: Graph<Node, Edge, Undirected>,
     start: NodeIndex,
     end: (NodeIndex, NodeIndex),
@@ -205,11 +271,89 @@ impl Map {
         }
 
         Self {
+            grid,
             graph,
             start: start_node,
             end: end_nodes,
         }
     }
+
+    fn dump_graph(&self) -> GraphDump {
+        let nodes = self
+            .graph
+            .node_indices()
+            .map(|index| NodeDump::new(index, &self.graph[index]))
+            .collect();
+
+        let edges = self
+            .graph
+            .edge_references()
+            .map(|edge| EdgeDump {
+                a: edge.source().index(),
+                b: edge.target().index(),
+                cost: edge.weight().cost(),
+            })
+            .collect();
+
+        GraphDump { nodes, edges }
+    }
+
+    /// The nodes of a `path` found by the search, with the cost of reaching each one in `results`.
+    fn dump_path(
+        &self,
+        results: &ResultSet<NodeIndex, u64>,
+        path: &[NodeIndex],
+    ) -> std::vec::Vec<PathStepDump> {
+        path.iter()
+            .map(|&index| PathStepDump {
+                node: NodeDump::new(index, &self.graph[index]),
+                cost: results
+                    .cost(index)
+                    .expect("Nodes on a path should have been reached"),
+            })
+            .collect()
+    }
+}
+
+/// The graph as written by `--dump`, with nodes referred to by id
+#[derive(Debug, Serialize)]
+struct GraphDump {
+    nodes: std::vec::Vec<NodeDump>,
+    edges: std::vec::Vec<EdgeDump>,
+}
+
+#[derive(Debug, Serialize)]
+struct NodeDump {
+    id: usize,
+    x: u32,
+    y: u32,
+    orientation: char,
+}
+
+impl NodeDump {
+    fn new(index: NodeIndex, node: &Node) -> Self {
+        let coordinates = node.coordinates();
+        Self {
+            id: index.index(),
+            x: coordinates.x,
+            y: coordinates.y,
+            orientation: node.orientation().display_char(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct EdgeDump {
+    a: usize,
+    b: usize,
+    cost: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct PathStepDump {
+    #[serde(flatten)]
+    node: NodeDump,
+    cost: u64,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -269,6 +413,73 @@ impl std::fmt::Debug for Edge {
     }
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a maze of about `size` by `size` tiles with a few loops, from `S` in the
+/// bottom-left corner to `E` in the top-right corner.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+    // Rooms sit on odd coordinates, with the tiles between them carved as passages
+    let rooms = (size.max(5) - 1) / 2;
+    let side = 2 * rooms + 1;
+    let mut open = vec![vec![false; side]; side];
+    let mut visited = vec![vec![false; rooms]; rooms];
+
+    visited[0][0] = true;
+    open[1][1] = true;
+    let mut stack = vec![(0usize, 0usize)];
+    while let Some(&(x, y)) = stack.last() {
+        let unvisited = DIRECTIONS
+            .iter()
+            .filter_map(|&(dx, dy)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+            .filter(|&(next_x, next_y)| {
+                next_x < rooms && next_y < rooms && !visited[next_y][next_x]
+            })
+            .collect::<Vec<_>>();
+
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        let (next_x, next_y) = *rng.choose(&unvisited);
+        visited[next_y][next_x] = true;
+        open[y + next_y + 1][x + next_x + 1] = true;
+        open[2 * next_y + 1][2 * next_x + 1] = true;
+        stack.push((next_x, next_y));
+    }
+
+    for _ in 0..rooms * rooms / 8 {
+        let x = rng.index(side - 2) + 1;
+        let y = rng.index(side - 2) + 1;
+        if (x + y) % 2 == 1 {
+            open[y][x] = true;
+        }
+    }
+
+    let mut output = String::new();
+    for (y, row) in open.iter().enumerate() {
+        for (x, &is_open) in row.iter().enumerate() {
+            let tile = match (x, y) {
+                _ if !is_open => '#',
+                (1, y) if y == side - 2 => 'S',
+                (x, 1) if x == side - 2 => 'E',
+                _ => '.',
+            };
+
+            output.push(tile);
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,7 +492,7 @@ mod tests {
     #[case(EXAMPLE_1, 7036)]
     #[case(EXAMPLE_2, 11048)]
     fn test_part_1(#[case] input: &str, #[case] expected: u64) {
-        let result = part_1(input).unwrap();
+        let result = part_1(&parse(input).unwrap()).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -289,10 +500,42 @@ mod tests {
     #[case(EXAMPLE_1, 45)]
     #[case(EXAMPLE_2, 64)]
     fn test_part_2(#[case] input: &str, #[case] expected: u64) {
-        let result = part_2(input).unwrap();
+        let result = part_2(&parse(input).unwrap()).unwrap();
         assert_eq!(result, expected);
     }
 
+    /// Two routes around a block, one of them with an extra turn.
+    const LOOP: &str = "\
+########
+#.....E#
+#.####.#
+#.####.#
+#S.....#
+########
+";
+
+    #[rstest]
+    #[case(EXAMPLE_1)]
+    #[case(EXAMPLE_2)]
+    #[case(LOOP)]
+    fn test_dump_path(#[case] input: &str) {
+        let map = parse(input).unwrap();
+        let results = search_map(&map);
+        let end = if results.cost(map.end.0) == find_cheapest_path_cost(&map) {
+            map.end.0
+        } else {
+            map.end.1
+        };
+
+        let steps = map.dump_path(&results, &results.path(end).unwrap());
+        assert_eq!(steps[0].cost, 0);
+        assert!(steps.windows(2).all(|pair| pair[0].cost < pair[1].cost));
+        assert_eq!(
+            steps.last().map(|step| step.cost),
+            find_cheapest_path_cost(&map)
+        );
+    }
+
     // #[rstest]
     // #[case(INPUT, "")]
     // #[case(EXAMPLE_1, "example.1")]
@@ -312,4 +555,11 @@ mod tests {
     //     let mut file = File::create(&path).unwrap();
     //     write!(file, "{:?}", Dot::new(&map.graph))
     // }
+
+    #[test]
+    fn test_generate() {
+        let map = parse(&generate(&mut Rng::new(1), 10)).unwrap();
+        part_1(&map).unwrap();
+        part_2(&map).unwrap();
+    }
 }
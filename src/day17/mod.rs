@@ -2,6 +2,8 @@ use aoc_utils::{str::StrExt, AocError};
 use regex::Regex;
 use vm::{Trace, TraceEntry};
 
+use crate::{generate::Rng, input, variant::VariantBuilder};
+
 use self::vm::Vm;
 
 mod vm;
@@ -12,8 +14,12 @@ pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
+}
+
+pub fn variants(builder: &mut VariantBuilder) {
+    builder.add_variant(2, "suffix", || part_2_suffix(&input::get(INPUT)));
 }
 
 fn part_1(_input: &str) -> anyhow::Result<String> {
@@ -51,6 +57,12 @@ fn part_2(input: &str) -> anyhow::Result<i64> {
     Ok(result)
 }
 
+fn part_2_suffix(input: &str) -> anyhow::Result<i64> {
+    let (mut vm, program) = parse(input)?;
+    let result = find_quine_suffix(&mut vm, &program)?;
+    Ok(result)
+}
+
 fn parse(input: &str) -> anyhow::Result<(Vm, Vec<i64>)> {
     let mut paragraphs = input.paragraphs();
     let vm_input = paragraphs.next().ok_or(AocError::InvalidInput)?;
@@ -100,6 +112,38 @@ fn find_quine<T: Trace>(vm: &mut Vm<T>, program: &[i64]) -> anyhow::Result<i64>
     recurse(vm, program, 0, 15)
 }
 
+/// Builds register A one octal digit at a time, keeping the values whose output is the end of the
+/// program, since each loop outputs a digit and shifts it out of A.
+fn find_quine_suffix<T: Trace>(vm: &mut Vm<T>, program: &[i64]) -> anyhow::Result<i64> {
+    let mut candidates = vec![0i64];
+    for length in 1..=program.len() {
+        let suffix = &program[program.len() - length..];
+
+        let mut next = Vec::new();
+        for candidate in candidates {
+            for digit in 0..8 {
+                let Some(a) = candidate.checked_mul(8).map(|a| a + digit) else {
+                    continue;
+                };
+
+                vm.reset();
+                vm.set_registers([a, 0, 0]);
+                if vm.execute_program(program)? == suffix {
+                    next.push(a);
+                }
+            }
+        }
+
+        candidates = next;
+    }
+
+    let result = candidates
+        .into_iter()
+        .min()
+        .ok_or_else(|| AocError::message("No value of A outputs the program"))?;
+    Ok(result)
+}
+
 fn parse_vm(input: &str) -> anyhow::Result<Vm> {
     let regex = Regex::new(r"[0-9]+").unwrap();
 
@@ -133,6 +177,33 @@ fn parse_program(input: &str) -> anyhow::Result<Vec<i64>> {
     }
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a program shaped like the puzzle's, which outputs one octal digit of register A
+/// per loop, retrying until it has a quine solution. `size` is ignored.
+pub fn generate(rng: &mut Rng, _size: usize) -> String {
+    loop {
+        let mut body = vec![[1, rng.range(0..=7)], [4, rng.range(0..=7)], [0, 3]];
+        rng.shuffle(&mut body);
+
+        let mut program = vec![2, 4, 1, rng.range(0..=7), 7, 5];
+        program.extend(body.into_iter().flatten());
+        program.extend([5, 5, 3, 0]);
+
+        let mut vm = Vm::new();
+        if find_quine(&mut vm, &program).is_ok_and(|a| a != i64::MAX) {
+            let a = rng.range(1..=1 << 47);
+            return format!(
+                "Register A: {a}\nRegister B: 0\nRegister C: 0\n\nProgram: {}\n",
+                format_output(&program)
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,4 +220,21 @@ mod tests {
     fn test_part_2() {
         let _result = part_2(EXAMPLE_1).unwrap();
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
+
+    #[test]
+    fn test_part_2_variants() {
+        for seed in 0..8 {
+            let input = generate(&mut Rng::new(seed), 10);
+            let digits = part_2(&input).unwrap();
+            let suffix = part_2_suffix(&input).unwrap();
+            assert_eq!(digits, suffix, "seed {seed}");
+        }
+    }
 }
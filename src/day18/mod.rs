@@ -1,58 +1,71 @@
-use std::collections::VecDeque;
-
 use aoc_utils::{
     grid::{Coordinates, Grid},
-    hashbrown::{hash_map::Entry, HashMap},
     neighbors::CardinalNeighbors,
     parser::VectorParseExt,
     AocError,
 };
 
+use crate::{animate::Animation, generate::Rng, input, render, search};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT, 1024, 71, 71));
-    builder.add_part(|| part_2(INPUT, 71, 71));
+    builder.add_part(|| {
+        let (width, height) = (input::param("width", 71)?, input::param("height", 71)?);
+        part_1(
+            &input::get(INPUT),
+            input::param("time", 1024)?,
+            width,
+            height,
+        )
+    });
+    builder.add_part(|| {
+        let (width, height) = (input::param("width", 71)?, input::param("height", 71)?);
+        part_2(&input::get(INPUT), width, height)
+    });
 }
 
 fn part_1(input: &str, time: u32, width: u32, height: u32) -> anyhow::Result<u32> {
     let (grid, _) = parse(input, width, height)?;
+
+    let mut animation = Animation::new("day18");
+    for fallen in 0..=time {
+        animation.frame(|| byte_chars(&grid, fallen), render::char_palette);
+    }
+
     find_path_length(&grid, time).ok_or(AocError::message("Unable to find path").into())
 }
 
 fn find_path_length(grid: &Grid<u32>, time: u32) -> Option<u32> {
     let target = Coordinates::new(grid.width() - 1, grid.height() - 1);
 
-    let mut visited = HashMap::new();
-    let mut queue = VecDeque::new();
-    queue.push_back((Coordinates::zeros(), 0));
-
-    while let Some((node, step)) = queue.pop_front() {
-        match visited.entry(node) {
-            Entry::Occupied(_) => continue,
-            Entry::Vacant(vacant) => {
-                vacant.insert(step);
-            }
-        }
+    let successors = |node| {
+        CardinalNeighbors::new(node).filter(move |&neighbor| {
+            grid.get(neighbor)
+                .is_some_and(|&byte_index| byte_index >= time)
+        })
+    };
 
-        if node == target {
-            return Some(step);
-        }
+    let (_, results) = search::bfs_until(Coordinates::zeros(), successors, |node| node == target)?;
+    results.cost(target).map(|cost| cost as u32)
+}
 
-        for neighbor in CardinalNeighbors::new(node) {
-            if grid
-                .get(neighbor)
-                .is_some_and(|&byte_index| byte_index >= time)
-            {
-                queue.push_back((neighbor, step + 1));
+fn byte_chars(grid: &Grid<u32>, fallen: u32) -> Grid<char> {
+    let mut chars = Grid::new_with(grid.width(), grid.height(), || '.');
+    for (y, row) in grid.rows().enumerate() {
+        for (x, &byte_index) in row.iter().enumerate() {
+            if byte_index < fallen {
+                // Highlight the byte that fell last
+                let c = if byte_index + 1 == fallen { '*' } else { '#' };
+                chars[Coordinates::new(x as u32, y as u32)] = c;
             }
         }
     }
 
-    None
+    chars
 }
 
 fn part_2(input: &str, width: u32, height: u32) -> anyhow::Result<String> {
@@ -90,12 +103,70 @@ fn parse(input: &str, width: u32, height: u32) -> anyhow::Result<(Grid<u32>, Vec
     for (index, line) in input.lines().enumerate() {
         let coordinates = Coordinates::from_str_radix(line, 10)?;
         bytes.push(coordinates);
-        grid[coordinates] = index as u32;
+        *grid.get_mut(coordinates).ok_or(AocError::InvalidInput)? = index as u32;
     }
 
     Ok((grid, bytes))
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input, 71, 71);
+}
+
+/// Generates at least `size` bytes falling into the runner's 71 by 71 memory space. The exit
+/// stays reachable after the first kilobyte and is blocked by one of the generated bytes.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const SIDE: u32 = 71;
+    const TIME: u32 = 1024;
+
+    fn memory(bytes: &[Coordinates]) -> Grid<u32> {
+        let mut grid = Grid::new_with(SIDE, SIDE, || u32::MAX);
+        for (index, &coordinates) in bytes.iter().enumerate() {
+            grid[coordinates] = index as u32;
+        }
+
+        grid
+    }
+
+    let target = Coordinates::new(SIDE - 1, SIDE - 1);
+    let mut cells = (0..SIDE * SIDE)
+        .map(|index| Coordinates::new(index % SIDE, index / SIDE))
+        .filter(|&coordinates| coordinates != Coordinates::zeros() && coordinates != target)
+        .collect::<Vec<_>>();
+
+    loop {
+        rng.shuffle(&mut cells);
+
+        let grid = memory(&cells);
+        if find_path_length(&grid, TIME).is_none() {
+            continue;
+        }
+
+        let Some(blocking) = find_first_byte_blocking_path(&grid, &cells)
+            .and_then(|byte| cells.iter().position(|&cell| cell == byte))
+        else {
+            continue;
+        };
+
+        let count = size
+            .max(blocking + 1)
+            .max(TIME as usize + 1)
+            .min(cells.len());
+        let bytes = &cells[..count];
+        if find_first_byte_blocking_path(&memory(bytes), bytes).is_none() {
+            continue;
+        }
+
+        let mut output = String::new();
+        for byte in bytes {
+            output.push_str(&format!("{},{}\n", byte.x, byte.y));
+        }
+
+        return output;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,4 +186,11 @@ mod tests {
         let result = part_2(EXAMPLE_1, 7, 7).unwrap();
         assert_eq!(result, "6,1");
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input, 1024, 71, 71).unwrap();
+        part_2(&input, 71, 71).unwrap();
+    }
 }
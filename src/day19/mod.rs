@@ -1,14 +1,16 @@
 use aoc_utils::{cache::Cache, AocError};
 use rayon::prelude::*;
 
+use crate::{cache::Instrumented, generate::Rng, input};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<u64> {
@@ -44,10 +46,11 @@ fn can_be_represented(pattern: &str, towels: &[&str]) -> bool {
 
     let mut cache = vec![None; pattern.len() + 1];
     cache[0] = Some(true);
+    let mut cache = Instrumented::new("day19-part1", cache);
 
     recurse(pattern, towels, &mut cache);
 
-    cache[pattern.len()].unwrap_or(false)
+    cache.inner()[pattern.len()].unwrap_or(false)
 }
 
 fn part_2(input: &str) -> anyhow::Result<u64> {
@@ -80,10 +83,11 @@ fn count_representations(pattern: &str, towels: &[&str]) -> u64 {
 
     let mut cache = vec![None; pattern.len() + 1];
     cache[0] = Some(1);
+    let mut cache = Instrumented::new("day19-part2", cache);
 
     recurse(pattern, towels, &mut cache);
 
-    cache[pattern.len()].unwrap_or(0)
+    cache.inner()[pattern.len()].unwrap_or(0)
 }
 
 fn parse(input: &str) -> anyhow::Result<(Vec<&str>, Vec<&str>)> {
@@ -101,6 +105,51 @@ fn parse(input: &str) -> anyhow::Result<(Vec<&str>, Vec<&str>)> {
     Ok((towels, patterns))
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates `size` towel patterns and `size` designs, about half of which are built from the
+/// available patterns.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const COLORS: &[u8] = b"wubrg";
+
+    let size = size.max(1);
+    let mut towels = Vec::new();
+    while towels.len() < size {
+        let len = rng.range(1..=8) as usize;
+        let towel = (0..len)
+            .map(|_| *rng.choose(COLORS) as char)
+            .collect::<String>();
+
+        // Without a single red stripe, random designs are usually impossible
+        if towel != "r" && !towels.contains(&towel) {
+            towels.push(towel);
+        }
+    }
+
+    let mut output = towels.join(", ");
+    output.push_str("\n\n");
+
+    for _ in 0..size {
+        let len = rng.range(10..=40) as usize;
+        let mut design = String::new();
+        if rng.chance(0.5) {
+            while design.len() < len {
+                design.push_str(rng.choose::<String>(&towels));
+            }
+        } else {
+            design.extend((0..len).map(|_| *rng.choose(COLORS) as char));
+        }
+
+        output.push_str(&design);
+        output.push('\n');
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,4 +167,11 @@ mod tests {
         let result = part_2(EXAMPLE_1).unwrap();
         assert_eq!(result, 16);
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
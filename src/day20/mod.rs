@@ -1,4 +1,3 @@
-use allocator_api2::vec::Vec;
 use aoc_utils::{
     grid::Coordinates,
     hashbrown::{HashMap, HashSet},
@@ -7,45 +6,60 @@ use aoc_utils::{
     AocError,
 };
 
+use serde::Serialize;
+
+use crate::{dump, generate::Rng, input, parsed::ParseStage, search};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT, 100));
-    builder.add_part(|| part_2(INPUT, 100));
+    builder
+        .parse_input(INPUT, parse)
+        .add_part(|map| part_1(map, input::param("threshold", 100)?))
+        .add_part(|map| part_2(map, input::param("threshold", 100)?));
 }
 
-fn part_1(input: &str, threshold: i64) -> anyhow::Result<u64> {
-    let map = parse(input)?;
-    let result = count_shortcuts(&map, threshold, 2);
+fn part_1(map: &Map, threshold: i64) -> anyhow::Result<u64> {
+    dump::artifact("day20-part1-cheats", || dump_shortcuts(map, threshold, 2));
+    let result = shortcuts(map, threshold, 2).count() as u64;
     Ok(result)
 }
 
-fn count_shortcuts(map: &Map, threshold: i64, limit: u32) -> u64 {
-    let mut count = 0;
-    for (&shortcut_start, start_distance) in map.tiles.iter() {
-        for (&node, &end_distance) in map.tiles.iter() {
-            let shortcut_len = manhattan_distance(shortcut_start, node);
+/// Cheats of at most `limit` picoseconds that save at least `threshold`.
+fn shortcuts(map: &Map, threshold: i64, limit: u32) -> impl Iterator<Item = Shortcut> + '_ {
+    map.tiles.iter().flat_map(move |(&start, start_distance)| {
+        map.tiles.iter().filter_map(move |(&end, &end_distance)| {
+            let shortcut_len = manhattan_distance(start, end);
             if shortcut_len > limit {
-                continue;
+                return None;
             }
 
-            let shortcut_distance = end_distance - start_distance - shortcut_len as i64;
-            if shortcut_distance >= threshold {
-                // tracing::debug!(?shortcut_start, ?node, shortcut_distance);
-                count += 1;
-            }
-        }
-    }
+            let saving = end_distance - start_distance - shortcut_len as i64;
+            (saving >= threshold).then_some(Shortcut { start, end, saving })
+        })
+    })
+}
 
-    count
+fn dump_shortcuts(map: &Map, threshold: i64, limit: u32) -> Vec<ShortcutDump> {
+    let mut shortcuts = shortcuts(map, threshold, limit)
+        .map(|shortcut| ShortcutDump {
+            start: [shortcut.start.x, shortcut.start.y],
+            end: [shortcut.end.x, shortcut.end.y],
+            saving: shortcut.saving,
+        })
+        .collect::<Vec<_>>();
+
+    // The tiles are hashed, so sort for stable output
+    shortcuts.sort_unstable_by_key(|s| (std::cmp::Reverse(s.saving), s.start, s.end));
+    shortcuts
 }
 
-fn part_2(input: &str, threshold: i64) -> anyhow::Result<u64> {
-    let map = parse(input)?;
-    let result = count_shortcuts(&map, threshold, 20);
+fn part_2(map: &Map, threshold: i64) -> anyhow::Result<u64> {
+    dump::artifact("day20-part2-cheats", || dump_shortcuts(map, threshold, 20));
+    let result = shortcuts(map, threshold, 20).count() as u64;
     Ok(result)
 }
 
@@ -82,25 +96,94 @@ struct Map {
 
 impl Map {
     pub fn new(tiles: HashSet<Coordinates>, start: Coordinates) -> Self {
-        let mut new = Self {
-            tiles: HashMap::with_capacity(tiles.len()),
-        };
+        let results = search::bfs(start, |node| {
+            CardinalNeighbors::new(node).filter(|neighbor| tiles.contains(neighbor))
+        });
+
+        Self {
+            tiles: results
+                .iter()
+                .map(|(node, distance)| (node, distance as i64))
+                .collect(),
+        }
+    }
+}
 
-        let mut stack = Vec::new();
-        stack.push((start, 0));
+#[derive(Debug, Clone, Copy)]
+struct Shortcut {
+    start: Coordinates,
+    end: Coordinates,
+    saving: i64,
+}
 
-        while let Some((node, distance)) = stack.pop() {
-            new.tiles.insert(node, distance);
+/// A cheat as written by `--dump`
+#[derive(Debug, Serialize)]
+struct ShortcutDump {
+    start: [u32; 2],
+    end: [u32; 2],
+    saving: i64,
+}
 
-            for neighbor in CardinalNeighbors::new(node) {
-                if tiles.contains(&neighbor) && !new.tiles.contains_key(&neighbor) {
-                    stack.push((neighbor, distance + 1))
-                }
-            }
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a single-lane racetrack winding through a `size` by `size` grid.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+    // Track tiles sit on odd coordinates, so that walls always separate distinct parts of the
+    // lane and the race has exactly one path
+    let cells = (size.max(5) - 1) / 2;
+    let side = 2 * cells + 1;
+    let mut track = vec![vec![false; side]; side];
+    let mut visited = vec![vec![false; cells]; cells];
+
+    let start = (rng.index(cells), rng.index(cells));
+    let (mut x, mut y) = start;
+    visited[y][x] = true;
+    track[2 * y + 1][2 * x + 1] = true;
+
+    loop {
+        let unvisited = DIRECTIONS
+            .iter()
+            .filter_map(|&(dx, dy)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+            .filter(|&(next_x, next_y)| {
+                next_x < cells && next_y < cells && !visited[next_y][next_x]
+            })
+            .collect::<Vec<_>>();
+
+        if unvisited.is_empty() {
+            break;
         }
 
-        new
+        let (next_x, next_y) = *rng.choose(&unvisited);
+        visited[next_y][next_x] = true;
+        track[y + next_y + 1][x + next_x + 1] = true;
+        track[2 * next_y + 1][2 * next_x + 1] = true;
+        (x, y) = (next_x, next_y);
     }
+
+    let tile_start = (2 * start.0 + 1, 2 * start.1 + 1);
+    let tile_end = (2 * x + 1, 2 * y + 1);
+    let mut output = String::new();
+    for (tile_y, row) in track.iter().enumerate() {
+        for (tile_x, &is_track) in row.iter().enumerate() {
+            let tile = match (tile_x, tile_y) {
+                coordinates if coordinates == tile_start => 'S',
+                coordinates if coordinates == tile_end => 'E',
+                _ if is_track => '.',
+                _ => '#',
+            };
+
+            output.push(tile);
+        }
+
+        output.push('\n');
+    }
+
+    output
 }
 
 #[cfg(test)]
@@ -112,14 +195,22 @@ mod tests {
     #[test]
     fn test_part_1() {
         aoc_utils::tracing::setup_tracing(crate::AOC_LOG);
-        let result = part_1(EXAMPLE_1, 10).unwrap();
+        let result = part_1(&parse(EXAMPLE_1).unwrap(), 10).unwrap();
         assert_eq!(result, 10);
     }
 
     #[test]
     fn test_part_2() {
         aoc_utils::tracing::setup_tracing(crate::AOC_LOG);
-        let result = part_2(EXAMPLE_1, 50).unwrap();
+        let result = part_2(&parse(EXAMPLE_1).unwrap(), 50).unwrap();
         assert_eq!(result, 285);
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        let map = parse(&input).unwrap();
+        part_1(&map, 100).unwrap();
+        part_2(&map, 100).unwrap();
+    }
 }
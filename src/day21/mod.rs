@@ -1,4 +1,6 @@
-use aoc_utils::{cache::Cache, hashbrown::HashMap};
+use aoc_utils::{cache::Cache, hashbrown::HashMap, AocError};
+
+use crate::{cache::Instrumented, generate::Rng, input};
 
 use self::{
     keypad::{Dpad, DpadButton, Numpad, NumpadButton},
@@ -7,6 +9,8 @@ use self::{
 
 mod keypad;
 mod path;
+#[cfg(all(test, feature = "reference"))]
+mod reference;
 
 const INPUT: &str = include_str!("input.txt");
 
@@ -14,15 +18,15 @@ pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<u64> {
     let codes = parse(input)?;
 
     let mut total = 0;
-    let mut cache = HashMap::new();
+    let mut cache = Instrumented::new("day21-part1", HashMap::new());
     for code in codes.iter() {
         let length = code_length(code, 3, &mut cache);
         total += code.value * length;
@@ -76,7 +80,7 @@ fn part_2(input: &str) -> anyhow::Result<u64> {
     let codes = parse(input)?;
 
     let mut total = 0;
-    let mut cache = HashMap::new();
+    let mut cache = Instrumented::new("day21-part2", HashMap::new());
     for code in codes.iter() {
         let length = code_length(code, 26, &mut cache);
         total += code.value * length;
@@ -92,7 +96,11 @@ fn parse(input: &str) -> anyhow::Result<Vec<Code>> {
         for c in input.chars() {
             if c.is_ascii_digit() {
                 let value = c.to_digit(10).unwrap() as u64;
-                code.value = code.value * 10 + value;
+                code.value = code
+                    .value
+                    .checked_mul(10)
+                    .and_then(|code_value| code_value.checked_add(value))
+                    .ok_or(AocError::InvalidInput)?;
             }
 
             let button = NumpadButton::from_char(c)?;
@@ -117,6 +125,18 @@ struct Code {
     value: u64,
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates `size` door codes of three digits followed by `A`.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| format!("{:03}A\n", rng.range(0..=999)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -134,5 +154,12 @@ mod tests {
     fn test_part_2() {
         let _result = part_2(EXAMPLE_1).unwrap();
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
 // v<<A>>^A<A>AvA<^AA>A<vAAA>^A
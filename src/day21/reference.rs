@@ -0,0 +1,140 @@
+use std::collections::VecDeque;
+
+use aoc_utils::hashbrown::HashSet;
+
+type Position = (i32, i32);
+
+const NUMPAD: [&str; 4] = ["789", "456", "123", " 0A"];
+const DPAD: [&str; 2] = [" ^A", "<v>"];
+
+pub fn part_1(input: &str) -> u64 {
+    input
+        .lines()
+        .map(|code| {
+            let value = code.trim_end_matches('A').parse::<u64>().unwrap();
+            value * presses(code, 2).unwrap()
+        })
+        .sum()
+}
+
+/// Button presses needed to type `code` through `robots` directional keypads, found with a
+/// breadth-first search over the positions of every robot arm.
+pub fn presses(code: &str, robots: usize) -> Option<u64> {
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct State {
+        /// Arms on the directional keypads, from the one we press, then the numeric keypad arm
+        arms: Vec<Position>,
+        typed: usize,
+    }
+
+    let code = code.chars().collect::<Vec<_>>();
+
+    let mut arms = vec![find(&DPAD, 'A'); robots];
+    arms.push(find(&NUMPAD, 'A'));
+    let start = State { arms, typed: 0 };
+
+    let mut visited = HashSet::<_>::from_iter([start.clone()]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((state, distance)) = queue.pop_front() {
+        if state.typed == code.len() {
+            return Some(distance);
+        }
+
+        for key in ['^', 'v', '<', '>', 'A'] {
+            let mut next = state.clone();
+            if press(&mut next.arms, &mut next.typed, &code, key).is_some()
+                && visited.insert(next.clone())
+            {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Applies one press on our own keypad, or returns `None` if it would point an arm at a gap or
+/// type the wrong digit.
+fn press(arms: &mut [Position], typed: &mut usize, code: &[char], mut key: char) -> Option<()> {
+    let last = arms.len() - 1;
+    for (level, arm) in arms.iter_mut().enumerate() {
+        let keypad = if level == last {
+            &NUMPAD[..]
+        } else {
+            &DPAD[..]
+        };
+
+        let (dx, dy) = match key {
+            '^' => (0, -1),
+            'v' => (0, 1),
+            '<' => (-1, 0),
+            '>' => (1, 0),
+            _ => {
+                let pressed = key_at(keypad, *arm)?;
+                if level == last {
+                    (code.get(*typed) == Some(&pressed)).then_some(())?;
+                    *typed += 1;
+                    return Some(());
+                }
+
+                key = pressed;
+                continue;
+            }
+        };
+
+        let moved = (arm.0 + dx, arm.1 + dy);
+        key_at(keypad, moved)?;
+        *arm = moved;
+        return Some(());
+    }
+
+    unreachable!("The numeric keypad always consumes the press")
+}
+
+fn find(keypad: &[&str], key: char) -> Position {
+    for (y, row) in keypad.iter().enumerate() {
+        if let Some(x) = row.chars().position(|c| c == key) {
+            return (x as i32, y as i32);
+        }
+    }
+
+    unreachable!("Key must be on the keypad")
+}
+
+fn key_at(keypad: &[&str], (x, y): Position) -> Option<char> {
+    let row = keypad.get(usize::try_from(y).ok()?)?;
+    let key = row.chars().nth(usize::try_from(x).ok()?)?;
+    (key != ' ').then_some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::hashbrown::HashMap;
+
+    use super::*;
+    use crate::generate::Rng;
+
+    #[test]
+    fn test_matches_reference() {
+        for seed in 0..10 {
+            let input = super::super::generate(&mut Rng::new(seed), 5);
+            assert_eq!(
+                super::super::part_1(&input).unwrap(),
+                part_1(&input),
+                "seed {seed}"
+            );
+
+            let codes = super::super::parse(&input).unwrap();
+            for (code, line) in codes.iter().zip(input.lines()) {
+                for robots in 0..=3 {
+                    let mut cache = HashMap::new();
+                    assert_eq!(
+                        Some(super::super::code_length(code, robots + 1, &mut cache)),
+                        presses(line, robots),
+                        "code {line}, {robots} robots"
+                    );
+                }
+            }
+        }
+    }
+}
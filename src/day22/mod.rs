@@ -2,25 +2,30 @@ use aoc_utils::{hashbrown::HashMap, AocError};
 use itertools::Itertools;
 use rayon::prelude::*;
 
+use crate::{generate::Rng, input, progress::Progress};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<u64> {
     let numbers = parse(input)?;
 
+    let progress = Progress::new("day22", numbers.len() as u64);
     let result = numbers
         .par_iter()
         .map(|&n| {
-            SecretNumberIter(n)
+            let secret = SecretNumberIter(n)
                 .nth(2000)
-                .expect("SecretNumberIter is an infinite iterator")
+                .expect("SecretNumberIter is an infinite iterator");
+            progress.inc();
+            secret
         })
         .sum();
 
@@ -30,9 +35,14 @@ fn part_1(input: &str) -> anyhow::Result<u64> {
 fn part_2(input: &str) -> anyhow::Result<u64> {
     let numbers = parse(input)?;
 
+    let progress = Progress::new("day22", numbers.len() as u64);
     let result = numbers
         .par_iter()
-        .map(|&num| all_sequences(num, 2000))
+        .map(|&num| {
+            let sequences = all_sequences(num, 2000);
+            progress.inc();
+            sequences
+        })
         .reduce(
             || HashMap::new(),
             |mut a, b| {
@@ -113,6 +123,18 @@ impl Iterator for SecretNumberIter {
     }
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates `size` initial secret numbers.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| format!("{}\n", rng.range(1..=(1 << 24) - 1)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,4 +166,11 @@ mod tests {
         let result = part_2(EXAMPLE_2).unwrap();
         assert_eq!(result, 23);
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
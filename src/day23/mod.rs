@@ -6,14 +6,16 @@ use aoc_utils::{
 };
 use itertools::Itertools;
 
+use crate::{generate::Rng, input};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
+    builder.add_part(|| part_2(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<i64> {
@@ -150,6 +152,57 @@ fn parse(input: &str) -> anyhow::Result<Graph<&str, (), Undirected>> {
     Ok(graph)
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates a network of `size` computers with random links around one planted LAN party.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.clamp(4, 26 * 26);
+
+    let mut names = (b'a'..=b'z')
+        .flat_map(|a| (b'a'..=b'z').map(move |b| format!("{}{}", a as char, b as char)))
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut names);
+    names.truncate(size);
+
+    let mut links = HashSet::new();
+    let mut link = |a: usize, b: usize| {
+        if a != b {
+            links.insert(min_max(a, b));
+        }
+    };
+
+    // The first computers form the largest LAN party
+    let party = (size / 4).clamp(3, 13);
+    for a in 0..party {
+        for b in a + 1..party {
+            link(a, b);
+        }
+    }
+
+    for a in party..size {
+        link(a, rng.index(size));
+    }
+
+    for _ in 0..2 * size {
+        link(rng.index(size), rng.index(size));
+    }
+
+    let mut links = Vec::from_iter(links);
+    links.sort_unstable();
+    rng.shuffle(&mut links);
+
+    let mut output = String::new();
+    for (a, b) in links {
+        let (a, b) = if rng.chance(0.5) { (a, b) } else { (b, a) };
+        output.push_str(&format!("{}-{}\n", names[a], names[b]));
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,4 +245,11 @@ mod tests {
     //         Dot::with_config(&graph, &[Config::EdgeNoLabel])
     //     )
     // }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+        part_2(&input).unwrap();
+    }
 }
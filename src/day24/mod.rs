@@ -1,9 +1,14 @@
-use std::{collections::VecDeque, rc::Rc};
+use std::{collections::VecDeque, sync::Arc};
 
 use aoc_utils::{cache::Cache, hashbrown::HashMap, AocError};
 use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{cache::Instrumented, dump, generate::Rng, input, parsed::ParseStage, render};
 
 mod parser;
+#[cfg(all(test, feature = "reference"))]
+mod reference;
 
 const INPUT: &str = include_str!("input.txt");
 
@@ -11,13 +16,14 @@ pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder
+        .parse_input(INPUT, parser::parse)
+        .add_part(part_1)
+        .add_part(part_2);
 }
 
-fn part_1(input: &str) -> anyhow::Result<u64> {
-    let (x, y, device) = self::parser::parse(input)?;
-    let result = evaluate_device(&device, x, y);
+fn part_1((x, y, device): &(u64, u64, Device)) -> anyhow::Result<u64> {
+    let result = evaluate_device(device, *x, *y);
     Ok(result)
 }
 
@@ -52,6 +58,7 @@ fn evaluate_device(device: &Device, x: u64, y: u64) -> u64 {
         values[index as usize] = Some(y >> bit & 1 != 0);
     }
 
+    let mut values = Instrumented::new("day24-part1", values);
     let mut z = 0;
     for (bit, &index) in device.z.iter().enumerate() {
         let value = recurse(device, index, &mut values);
@@ -61,26 +68,45 @@ fn evaluate_device(device: &Device, x: u64, y: u64) -> u64 {
     z
 }
 
-fn part_2(input: &str) -> anyhow::Result<String> {
-    let (_, _, device) = self::parser::parse(input)?;
-    let swapped_wires = find_swapped_wires(&device);
+fn part_2((_, _, device): &(u64, u64, Device)) -> anyhow::Result<String> {
+    let swapped_wires = find_swapped_wires(device);
+
+    if let Some(file) = render::create("day24.dot") {
+        if let Err(e) = file.and_then(|mut file| {
+            write_graph(&mut file, device)?;
+            std::io::Write::flush(&mut file)
+        }) {
+            tracing::warn!(%e, "Unable to write the device graph");
+        }
+    }
+
+    dump::artifact("day24-swaps", || {
+        swapped_wires
+            .iter()
+            .map(|&(index, rule)| SwapDump {
+                wire: device[index].wire.to_string(),
+                rule,
+            })
+            .sorted_unstable_by(|a, b| a.wire.cmp(&b.wire))
+            .collect::<Vec<_>>()
+    });
 
     let result = swapped_wires
         .into_iter()
-        .map(|index| format!("{}", device[index].wire))
+        .map(|(index, _)| format!("{}", device[index].wire))
         .sorted_unstable()
         .join(",");
     Ok(result)
 }
 
-fn find_swapped_wires(device: &Device) -> Vec<u32> {
-    let mut swapped = Vec::new();
+/// Wires that break the structure of a ripple-carry adder, with the first rule each one broke.
+fn find_swapped_wires(device: &Device) -> Vec<(u32, &'static str)> {
+    let mut swapped = Vec::<(u32, &'static str)>::new();
     let mut queue = VecDeque::from_iter(device.z.iter().copied());
 
-    let mut emit = |index: u32, _reason: &'static str| {
-        if !swapped.contains(&index) {
-            swapped.push(index);
-            // tracing::trace!(wire = %device[index].wire.clone(), reason, "swapped wire");
+    let mut emit = |index: u32, reason: &'static str| {
+        if swapped.iter().all(|&(swapped, _)| swapped != index) {
+            swapped.push((index, reason));
         }
     };
 
@@ -198,6 +224,13 @@ fn check_node(device: &Device, node: &Node, emit: &mut impl FnMut(u32, &'static
     }
 }
 
+/// A swapped wire as written by `--dump`
+#[derive(Debug, Serialize)]
+struct SwapDump {
+    wire: String,
+    rule: &'static str,
+}
+
 #[derive(Debug, Clone)]
 struct Device {
     index: HashMap<Wire, u32>,
@@ -320,7 +353,7 @@ enum Wire {
     X(u32),
     Y(u32),
     Z(u32),
-    Name(Rc<str>),
+    Name(Arc<str>),
 }
 
 impl Wire {
@@ -417,13 +450,7 @@ enum GateKind {
     Xor,
 }
 
-#[allow(dead_code)]
-fn print_graph() -> anyhow::Result<()> {
-    use std::{fs::File, io::Write};
-
-    let (_, _, device) = self::parser::parse(INPUT)?;
-    let mut file = File::create("output/day24.dot")?;
-
+fn write_graph(file: &mut impl std::io::Write, device: &Device) -> std::io::Result<()> {
     writeln!(file, "digraph {{")?;
 
     for (index, node) in device.graph.iter().enumerate() {
@@ -455,6 +482,91 @@ fn print_graph() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = self::parser::parse(input);
+}
+
+/// Generates a ripple-carry adder of `size` bits (at least 6, at most 44) in which the `swaps`
+/// parameter (4 by default) pairs of gate outputs are swapped, each pair within a single full
+/// adder.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let bits = size.clamp(6, 44);
+    let swaps = input::param("swaps", 4_i64).expect("Any value should fit in an i64");
+
+    let mut names = aoc_utils::hashbrown::HashSet::new();
+    let mut name = |rng: &mut Rng| loop {
+        // Names never start with `x`, `y` or `z` so they cannot be mistaken for input or output
+        // wires
+        let name = (0..3)
+            .map(|index| (b'a' + rng.below(if index == 0 { 23 } else { 26 }) as u8) as char)
+            .collect::<String>();
+
+        if names.insert(name.clone()) {
+            return name;
+        }
+    };
+
+    let wire = |prefix: char, bit: usize| format!("{prefix}{bit:02}");
+    let (x, y, z) = (
+        |bit| wire('x', bit),
+        |bit| wire('y', bit),
+        |bit| wire('z', bit),
+    );
+
+    let mut gates = vec![(x(0), "XOR", y(0), z(0))];
+    let mut carry = name(rng);
+    gates.push((x(0), "AND", y(0), carry.clone()));
+
+    let mut adders = Vec::new();
+    for bit in 1..bits {
+        let sum = name(rng);
+        let direct_carry = name(rng);
+        let propagated_carry = name(rng);
+        let next_carry = if bit == bits - 1 { z(bits) } else { name(rng) };
+
+        adders.push(gates.len());
+        gates.push((x(bit), "XOR", y(bit), sum.clone()));
+        gates.push((x(bit), "AND", y(bit), direct_carry.clone()));
+        gates.push((sum.clone(), "XOR", carry.clone(), z(bit)));
+        gates.push((sum, "AND", carry, propagated_carry.clone()));
+        gates.push((propagated_carry, "OR", direct_carry, next_carry.clone()));
+        carry = next_carry;
+    }
+
+    // Swaps are picked among the kinds found in puzzle inputs, none of which create cycles. The
+    // last adder is left alone so that the final carry remains the top output bit, and so is the
+    // first one: its carry input comes straight from an `AND`, which the rules don't cover. As in
+    // puzzle inputs no two neighbouring adders are swapped, or the rules could miss one of them.
+    adders.pop();
+    adders.remove(0);
+    let mut adders = adders.into_iter().step_by(2).collect::<Vec<_>>();
+    rng.shuffle(&mut adders);
+    // There can be no more swaps than adders
+    for &adder in adders.iter().take(swaps.max(0) as usize) {
+        let (a, b) = *rng.choose(&[(2, 4), (2, 3), (2, 1), (0, 1)]);
+        let output = std::mem::take(&mut gates[adder + a].3);
+        gates[adder + a].3 = std::mem::replace(&mut gates[adder + b].3, output);
+    }
+
+    let mut output = String::new();
+    for prefix in ['x', 'y'] {
+        for bit in 0..bits {
+            output.push_str(&format!("{}: {}\n", wire(prefix, bit), rng.below(2)));
+        }
+    }
+
+    output.push('\n');
+
+    rng.shuffle(&mut gates);
+    for (a, gate, b, wire) in gates {
+        let (a, b) = if rng.chance(0.5) { (a, b) } else { (b, a) };
+        output.push_str(&format!("{a} {gate} {b} -> {wire}\n"));
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -467,12 +579,31 @@ mod tests {
     #[case(EXAMPLE_1, 4)]
     #[case(EXAMPLE_2, 2024)]
     fn test_part_1(#[case] input: &str, #[case] expected: u64) {
-        let result = part_1(input).unwrap();
+        let result = part_1(&parser::parse(input).unwrap()).unwrap();
         assert_eq!(result, expected);
     }
 
     #[test]
     fn test_part_2() {
-        let _result = part_2(EXAMPLE_1).unwrap();
+        let _result = part_2(&parser::parse(EXAMPLE_1).unwrap()).unwrap();
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(2)]
+    #[case(4)]
+    #[case(7)]
+    fn test_generate_swaps(#[case] swaps: i64) {
+        let params = [("swaps".to_string(), swaps)].into_iter().collect();
+        let input = input::with_params(params, || generate(&mut Rng::new(3), 20));
+        let (_, _, device) = parser::parse(&input).unwrap();
+        assert_eq!(find_swapped_wires(&device).len(), 2 * swaps as usize);
+    }
+
+    #[test]
+    fn test_generate() {
+        let parsed = parser::parse(&generate(&mut Rng::new(1), 10)).unwrap();
+        part_1(&parsed).unwrap();
+        part_2(&parsed).unwrap();
     }
 }
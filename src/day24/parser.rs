@@ -3,7 +3,7 @@ use aoc_utils::{
         branch::alt,
         bytes::complete::tag,
         character::complete::{alphanumeric1, line_ending, space1},
-        combinator::{map, value},
+        combinator::{map, value, verify},
         error::ParseError,
         multi::fold_many1,
         sequence::{separated_pair, terminated, tuple},
@@ -58,7 +58,11 @@ fn device<'input, E: ParseError<&'input str>>(
 }
 
 fn wire<'input, E: ParseError<&'input str>>(input: &'input str) -> IResult<&'input str, Wire, E> {
-    map(alphanumeric1, Wire::new)(input)
+    // Input and output bits must fit in the device's `u64`s
+    verify(map(alphanumeric1, Wire::new), |wire| match wire {
+        Wire::X(bit) | Wire::Y(bit) | Wire::Z(bit) => (*bit as usize) < Device::SIZE,
+        Wire::Name(_) => true,
+    })(input)
 }
 
 fn gate_kind<'input, E: ParseError<&'input str>>(
@@ -0,0 +1,253 @@
+use aoc_utils::hashbrown::HashMap;
+use itertools::Itertools;
+
+use crate::{generate::Rng, input};
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    And,
+    Or,
+    Xor,
+}
+
+#[derive(Debug, Clone)]
+struct Circuit {
+    names: Vec<String>,
+    /// Gate driving each wire, if any
+    drivers: Vec<Option<(usize, Op, usize)>>,
+    x: Vec<usize>,
+    y: Vec<usize>,
+    z: Vec<usize>,
+    initial: (u64, u64),
+}
+
+pub fn part_1(input: &str) -> u64 {
+    let circuit = parse(input);
+    let (x, y) = circuit.initial;
+    evaluate(&circuit.drivers, &circuit, x, y).unwrap()
+}
+
+/// Tries every set of disjoint swaps of two gate outputs, fewest swaps first and up to the
+/// `swaps` parameter, returning the wires of the only set that turns the circuit into an adder.
+pub fn part_2(input: &str) -> String {
+    let swaps = input::param("swaps", 4_usize).unwrap();
+    let circuit = parse(input);
+    assert!(
+        circuit.x.len() <= 8,
+        "Only circuits of up to 8 bits are checked"
+    );
+
+    let vectors = test_vectors(circuit.x.len());
+    let pairs = (0..circuit.drivers.len())
+        .filter(|&wire| circuit.drivers[wire].is_some())
+        .tuple_combinations()
+        .collect::<Vec<_>>();
+
+    for count in 0..=swaps {
+        let fixes = pairs
+            .iter()
+            .copied()
+            .combinations(count)
+            .filter(|set| set.iter().flat_map(|&(a, b)| [a, b]).all_unique())
+            .filter(|set| {
+                let mut drivers = circuit.drivers.clone();
+                for &(a, b) in set {
+                    drivers.swap(a, b);
+                }
+
+                // The test vectors rule out almost every set quickly, the few left are checked
+                // against every pair of inputs
+                adds(&circuit, &drivers, vectors.iter().copied())
+                    && adds(&circuit, &drivers, every_input(circuit.x.len()))
+            })
+            .collect::<Vec<_>>();
+
+        match &fixes[..] {
+            [] => continue,
+            [fix] => {
+                return fix
+                    .iter()
+                    .flat_map(|&(a, b)| [a, b])
+                    .map(|wire| circuit.names[wire].as_str())
+                    .sorted_unstable()
+                    .join(",")
+            }
+            _ => panic!("{} sets of {count} swaps fix the circuit", fixes.len()),
+        }
+    }
+
+    panic!("No {swaps} swaps fix the circuit");
+}
+
+fn test_vectors(bits: usize) -> Vec<(u64, u64)> {
+    let mut vectors = Vec::new();
+    for bit in 0..bits {
+        let value = 1 << bit;
+        vectors.extend([(value, 0), (0, value), (value, value), (value - 1, 1)]);
+    }
+
+    let mut rng = Rng::new(0);
+    let mask = (1 << bits) - 1;
+    vectors.extend((0..16).map(|_| (rng.next_u64() & mask, rng.next_u64() & mask)));
+    vectors
+}
+
+fn every_input(bits: usize) -> impl Iterator<Item = (u64, u64)> {
+    (0..1 << bits).cartesian_product(0..1 << bits)
+}
+
+/// Whether the circuit adds every pair of `inputs` without running into a cycle.
+fn adds(
+    circuit: &Circuit,
+    drivers: &[Option<(usize, Op, usize)>],
+    mut inputs: impl Iterator<Item = (u64, u64)>,
+) -> bool {
+    inputs.all(|(x, y)| evaluate(drivers, circuit, x, y) == Some(x + y))
+}
+
+/// Evaluates the output wires, or returns `None` if the gates form a cycle.
+fn evaluate(
+    drivers: &[Option<(usize, Op, usize)>],
+    circuit: &Circuit,
+    x: u64,
+    y: u64,
+) -> Option<u64> {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum State {
+        Unknown,
+        Visiting,
+        Known(bool),
+    }
+
+    fn value(
+        drivers: &[Option<(usize, Op, usize)>],
+        states: &mut [State],
+        wire: usize,
+    ) -> Option<bool> {
+        match states[wire] {
+            State::Known(value) => return Some(value),
+            State::Visiting => return None,
+            State::Unknown => {}
+        }
+
+        states[wire] = State::Visiting;
+        let (a, op, b) = drivers[wire]?;
+        let (a, b) = (value(drivers, states, a)?, value(drivers, states, b)?);
+        let value = match op {
+            Op::And => a && b,
+            Op::Or => a || b,
+            Op::Xor => a ^ b,
+        };
+
+        states[wire] = State::Known(value);
+        Some(value)
+    }
+
+    let mut states = vec![State::Unknown; drivers.len()];
+    for (bit, (&x_wire, &y_wire)) in circuit.x.iter().zip(circuit.y.iter()).enumerate() {
+        states[x_wire] = State::Known(x >> bit & 1 != 0);
+        states[y_wire] = State::Known(y >> bit & 1 != 0);
+    }
+
+    let mut z = 0;
+    for (bit, &wire) in circuit.z.iter().enumerate() {
+        z |= (value(drivers, &mut states, wire)? as u64) << bit;
+    }
+
+    Some(z)
+}
+
+fn parse(input: &str) -> Circuit {
+    let mut indices = HashMap::new();
+    let mut names = Vec::new();
+    let mut index = |name: &str| {
+        *indices.entry(name.to_owned()).or_insert_with(|| {
+            names.push(name.to_owned());
+            names.len() - 1
+        })
+    };
+
+    let (initial, gates) = input.split_once("\n\n").unwrap();
+
+    let mut values = HashMap::new();
+    for line in initial.lines() {
+        let (wire, value) = line.split_once(": ").unwrap();
+        values.insert(index(wire), value == "1");
+    }
+
+    let mut gate_list = Vec::new();
+    for line in gates.lines() {
+        let [a, op, b, _, output] = line.split(' ').collect::<Vec<_>>()[..] else {
+            panic!("Invalid gate {line}");
+        };
+
+        let op = match op {
+            "AND" => Op::And,
+            "OR" => Op::Or,
+            "XOR" => Op::Xor,
+            _ => panic!("Invalid gate {op}"),
+        };
+
+        gate_list.push((index(output), (index(a), op, index(b))));
+    }
+
+    let mut drivers = vec![None; names.len()];
+    for (output, gate) in gate_list {
+        drivers[output] = Some(gate);
+    }
+
+    let wires = |prefix: char| {
+        (0..)
+            .map_while(|bit| indices.get(&format!("{prefix}{bit:02}")).copied())
+            .collect::<Vec<_>>()
+    };
+
+    let (x, y, z) = (wires('x'), wires('y'), wires('z'));
+
+    let initial_value = |wires: &[usize]| {
+        wires
+            .iter()
+            .enumerate()
+            .map(|(bit, wire)| (values[wire] as u64) << bit)
+            .sum()
+    };
+
+    let initial = (initial_value(&x), initial_value(&y));
+
+    Circuit {
+        names,
+        drivers,
+        x,
+        y,
+        z,
+        initial,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_reference() {
+        // Two swaps keep the exhaustive search short
+        let params = [("swaps".to_string(), 2)].into_iter().collect();
+        input::with_params(params, || {
+            for seed in 0..10 {
+                let input = super::super::generate(&mut Rng::new(seed), 6);
+                let parsed = super::super::parser::parse(&input).unwrap();
+
+                assert_eq!(
+                    super::super::part_1(&parsed).unwrap(),
+                    part_1(&input),
+                    "part 1, seed {seed}"
+                );
+                assert_eq!(
+                    super::super::part_2(&parsed).unwrap(),
+                    part_2(&input),
+                    "part 2, seed {seed}"
+                );
+            }
+        });
+    }
+}
@@ -1,13 +1,15 @@
 use aoc_utils::{nalgebra, str::StrExt, AocError};
 use itertools::Itertools;
 
+use crate::{generate::Rng, input};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
+    builder.add_part(|| part_1(&input::get(INPUT)));
 }
 
 fn part_1(input: &str) -> anyhow::Result<usize> {
@@ -63,6 +65,39 @@ fn parse(input: &str) -> anyhow::Result<(Vec<Vector>, Vec<Vector>)> {
 
 type Vector = nalgebra::Vector5<i8>;
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+/// Generates `size` lock and key schematics.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut schematics = Vec::new();
+    for _ in 0..size.max(1) {
+        let is_lock = rng.chance(0.5);
+        let heights = [(); 5].map(|_| rng.range(0..=5) as usize);
+
+        let mut schematic = String::new();
+        for row in 0..7 {
+            for &height in heights.iter() {
+                let is_filled = if is_lock {
+                    row <= height
+                } else {
+                    row >= 6 - height
+                };
+
+                schematic.push(if is_filled { '#' } else { '.' });
+            }
+
+            schematic.push('\n');
+        }
+
+        schematics.push(schematic);
+    }
+
+    schematics.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,4 +109,10 @@ mod tests {
         let _result = part_1(EXAMPLE_1).unwrap();
         assert_eq!(_result, 3);
     }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(&mut Rng::new(1), 10);
+        part_1(&input).unwrap();
+    }
 }
@@ -0,0 +1,138 @@
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+thread_local! {
+    static SINK: RefCell<Option<Sink>> = const { RefCell::new(None) };
+}
+
+/// Directory the artifacts of a run are written to, which the runner hands to the parts it runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sink {
+    directory: PathBuf,
+}
+
+impl Sink {
+    /// A sink writing to `directory`, created if needed.
+    pub fn new(directory: PathBuf) -> io::Result<Self> {
+        std::fs::create_dir_all(&directory)?;
+        Ok(Self { directory })
+    }
+}
+
+/// Runs `f` with every part on the current thread writing its artifacts to `sink`, or nowhere.
+pub fn with_sink<R>(sink: Option<Sink>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Sink>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SINK.set(self.0.take());
+        }
+    }
+
+    let _restore = Restore(SINK.replace(sink));
+    f()
+}
+
+pub fn is_enabled() -> bool {
+    SINK.with_borrow(Option::is_some)
+}
+
+fn directory() -> Option<PathBuf> {
+    SINK.with_borrow(|sink| sink.as_ref().map(|sink| sink.directory.clone()))
+}
+
+/// Writes the value built by `f` to `name.json` if dumps are enabled.
+///
+/// `f` is only called when dumping, so artifacts can be expensive to build.
+pub fn artifact<T, F>(name: &str, f: F)
+where
+    T: Serialize,
+    F: FnOnce() -> T,
+{
+    let Some(directory) = directory() else {
+        return;
+    };
+
+    let path = directory.join(format!("{name}.json"));
+    if let Err(e) = write(&path, &f()) {
+        tracing::warn!(%e, path = %path.display(), "Unable to write dump");
+    }
+}
+
+/// Writes the text built by `f` to `file_name` if dumps are enabled, for artifacts in formats
+/// other than JSON.
+pub fn text<F>(file_name: &str, f: F)
+where
+    F: FnOnce() -> String,
+{
+    let Some(directory) = directory() else {
+        return;
+    };
+
+    let path = directory.join(file_name);
+    if let Err(e) = std::fs::write(&path, f()) {
+        tracing::warn!(%e, path = %path.display(), "Unable to write dump");
+    }
+}
+
+fn write<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut file, value)?;
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write() {
+        #[derive(Serialize)]
+        struct Artifact {
+            name: &'static str,
+            cells: Vec<[u32; 2]>,
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("advent-2024-dump-{}.json", std::process::id()));
+        let artifact = Artifact {
+            name: "test",
+            cells: vec![[0, 1], [2, 3]],
+        };
+        write(&path, &artifact).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let value: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({ "name": "test", "cells": [[0, 1], [2, 3]] })
+        );
+    }
+
+    #[test]
+    fn test_with_sink() {
+        let directory =
+            std::env::temp_dir().join(format!("advent-2024-sink-{}", std::process::id()));
+        let sink = Sink::new(directory.clone()).unwrap();
+
+        artifact("outside", || 1);
+        with_sink(Some(sink), || {
+            assert!(is_enabled());
+            artifact("inside", || 2);
+        });
+        assert!(!is_enabled());
+
+        let inside = std::fs::read_to_string(directory.join("inside.json"));
+        let outside = directory.join("outside.json").exists();
+        let _ = std::fs::remove_dir_all(&directory);
+        assert_eq!(inside.unwrap(), "2");
+        assert!(!outside);
+    }
+}
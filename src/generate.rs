@@ -0,0 +1,137 @@
+use std::ops::RangeInclusive;
+
+use aoc_utils::AocError;
+
+/// Generates a random input for `day`.
+///
+/// `size` scales the input; each day documents how it interprets it.
+pub fn generate(day: i32, seed: u64, size: usize) -> Result<String, AocError> {
+    let generator: fn(&mut Rng, usize) -> String = match day {
+        1 => crate::day01::generate,
+        2 => crate::day02::generate,
+        3 => crate::day03::generate,
+        4 => crate::day04::generate,
+        5 => crate::day05::generate,
+        6 => crate::day06::generate,
+        7 => crate::day07::generate,
+        8 => crate::day08::generate,
+        9 => crate::day09::generate,
+        10 => crate::day10::generate,
+        11 => crate::day11::generate,
+        12 => crate::day12::generate,
+        13 => crate::day13::generate,
+        14 => crate::day14::generate,
+        15 => crate::day15::generate,
+        16 => crate::day16::generate,
+        17 => crate::day17::generate,
+        18 => crate::day18::generate,
+        19 => crate::day19::generate,
+        20 => crate::day20::generate,
+        21 => crate::day21::generate,
+        22 => crate::day22::generate,
+        23 => crate::day23::generate,
+        24 => crate::day24::generate,
+        25 => crate::day25::generate,
+        _ => return Err(AocError::NoSuchProblem(day)),
+    };
+
+    let mut rng = Rng::new(seed);
+    Ok(generator(&mut rng, size))
+}
+
+/// Small deterministic random number generator (SplitMix64), so that generated inputs can be
+/// reproduced from their seed.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "Bound must not be zero");
+
+        // Reject the values that would bias the modulo
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "Range must not be empty");
+
+        let span = end.abs_diff(start);
+        let offset = if span == u64::MAX {
+            self.next_u64()
+        } else {
+            self.below(span + 1)
+        };
+
+        start.wrapping_add(offset as i64)
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.index(index + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_rng_range() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let value = rng.range(-3..=3);
+            assert!((-3..=3).contains(&value));
+        }
+
+        assert_eq!(rng.range(i64::MIN..=i64::MIN), i64::MIN);
+    }
+
+    #[test]
+    fn test_generate_unknown_day() {
+        assert!(generate(26, 0, 10).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use std::{borrow::Cow, cell::RefCell, collections::BTreeMap};
+
+use aoc_utils::AocError;
+
+thread_local! {
+    static OVERRIDE: RefCell<Option<String>> = const { RefCell::new(None) };
+    static PARAMS: RefCell<BTreeMap<String, i64>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Returns the input the runner selected for the current thread, or `default`, normalized.
+pub fn get(default: &'static str) -> Cow<'static, str> {
+    OVERRIDE.with_borrow(|input| match input {
+        Some(input) => Cow::Owned(input.clone()),
+        None => normalize(default),
+    })
+}
+
+/// Strips the byte order mark, the carriage returns and whitespace ending lines, and the blank
+/// lines at the end of `input`, and ends it with exactly one newline, so that inputs saved on
+/// Windows or copied without their final newline parse like the originals. Whitespace within lines
+/// is left alone.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    if is_normalized(input) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut output = String::with_capacity(input.len() + 1);
+    for line in input.lines() {
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    output.truncate(output.trim_end().len());
+    if !output.is_empty() {
+        output.push('\n');
+    }
+
+    Cow::Owned(output)
+}
+
+fn is_normalized(input: &str) -> bool {
+    input.is_empty()
+        || (input.ends_with('\n')
+            && input.trim_end().len() == input.len() - 1
+            && !input.contains("\r\n")
+            && input
+                .lines()
+                .all(|line| line.trim_end().len() == line.len()))
+}
+
+/// Runs `f` with every problem on the current thread reading `input` instead of its own input.
+pub fn with_override<R>(input: &str, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<String>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            OVERRIDE.set(self.0.take());
+        }
+    }
+
+    let _restore = Restore(OVERRIDE.replace(Some(normalize(input).into_owned())));
+    f()
+}
+
+/// Returns the parameter `name` the runner selected for the current thread, or `default`.
+pub fn param<T>(name: &str, default: T) -> Result<T, AocError>
+where
+    T: TryFrom<i64>,
+{
+    PARAMS.with_borrow(|params| match params.get(name) {
+        Some(&value) => T::try_from(value)
+            .map_err(|_| AocError::message(format!("Invalid value {value} for '{name}'"))),
+        None => Ok(default),
+    })
+}
+
+/// Runs `f` with every problem on the current thread reading parameters from `params`.
+pub fn with_params<R>(params: BTreeMap<String, i64>, f: impl FnOnce() -> R) -> R {
+    struct Restore(BTreeMap<String, i64>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            PARAMS.set(std::mem::take(&mut self.0));
+        }
+    }
+
+    let _restore = Restore(PARAMS.replace(params));
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::{
+        harness::SimpleHarness,
+        problem::{ProblemBuilder, ProblemCollection},
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    const DEFAULT: &str = "default\n";
+
+    #[test]
+    fn test_override() {
+        assert_eq!(get(DEFAULT), DEFAULT);
+
+        let result = with_override("other", || {
+            let nested = with_override("nested", || get(DEFAULT).into_owned());
+            (get(DEFAULT).into_owned(), nested)
+        });
+
+        assert_eq!(result, ("other\n".to_owned(), "nested\n".to_owned()));
+        assert_eq!(get(DEFAULT), DEFAULT);
+    }
+
+    #[rstest]
+    #[case("", "")]
+    #[case("a\nb\n", "a\nb\n")]
+    #[case("a\nb", "a\nb\n")]
+    #[case("a\r\nb\r\n", "a\nb\n")]
+    #[case("\u{feff}a\nb\n", "a\nb\n")]
+    #[case("a  \nb\t\n", "a\nb\n")]
+    #[case("47|53  \n61|13\t\n\n75,47\n", "47|53\n61|13\n\n75,47\n")]
+    #[case("a b\n", "a b\n")]
+    #[case("a\n\nb\n\n\n", "a\n\nb\n")]
+    #[case("a\rb\r\n", "a\rb\n")]
+    #[case(" \n\n", "")]
+    fn test_normalize(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(normalize(input), expected);
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    enum Variant {
+        Crlf,
+        Bom,
+        NoFinalNewline,
+        TrailingWhitespace,
+        TrailingBlankLines,
+    }
+
+    impl Variant {
+        fn apply(self, input: &str) -> String {
+            match self {
+                Self::Crlf => input.replace('\n', "\r\n"),
+                Self::Bom => format!("\u{feff}{input}"),
+                Self::NoFinalNewline => input.trim_end_matches('\n').to_owned(),
+                Self::TrailingWhitespace => input.replace('\n', " \t\n"),
+                Self::TrailingBlankLines => format!("{input} \t\n\n"),
+            }
+        }
+    }
+
+    type ProblemFn = fn(&mut ProblemBuilder<SimpleHarness>);
+
+    /// Runs every part of a problem on `input`, with the messages of errors and panics.
+    fn run(problem: ProblemFn, params: &[(&str, i64)], input: &str) -> Vec<Result<String, String>> {
+        let problems = ProblemCollection::builder(SimpleHarness)
+            .add_problem(0, problem)
+            .build();
+        let params = params
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value))
+            .collect();
+
+        with_params(params, || {
+            with_override(input, || {
+                problems
+                    .get(0)
+                    .unwrap()
+                    .iter()
+                    .map(
+                        |(_, runner)| match crate::boundary::catch(|| runner.run()) {
+                            Ok(Ok(output)) => Ok(output),
+                            Ok(Err(e)) => Err(format!("Error: {e}")),
+                            Err(panic) => Err(panic.to_string()),
+                        },
+                    )
+                    .collect()
+            })
+        })
+    }
+
+    #[rstest]
+    #[case(crate::day01::problem, &[], include_str!("day01/example.1.txt"), &[Some("11"), Some("31")])]
+    #[case(crate::day02::problem, &[], include_str!("day02/example.1.txt"), &[Some("2"), Some("4")])]
+    #[case(crate::day03::problem, &[], include_str!("day03/example.1.txt"), &[Some("161"), Some("161")])]
+    #[case(crate::day03::problem, &[], include_str!("day03/example.2.txt"), &[Some("161"), Some("48")])]
+    #[case(crate::day04::problem, &[], include_str!("day04/example.1.txt"), &[Some("18"), Some("9")])]
+    #[case(crate::day05::problem, &[], include_str!("day05/example.1.txt"), &[Some("143"), Some("123")])]
+    #[case(crate::day06::problem, &[], include_str!("day06/example.1.txt"), &[Some("41"), Some("6")])]
+    #[case(crate::day07::problem, &[], include_str!("day07/example.1.txt"), &[Some("3749"), Some("11387")])]
+    #[case(crate::day08::problem, &[], include_str!("day08/example.1.txt"), &[Some("14"), Some("34")])]
+    #[case(crate::day09::problem, &[], include_str!("day09/example.1.txt"), &[Some("1928"), Some("2858")])]
+    #[case(crate::day09::problem, &[], include_str!("day09/example.2.txt"), &[Some("62"), Some("132")])]
+    #[case(crate::day10::problem, &[], include_str!("day10/example.1.txt"), &[Some("1"), Some("16")])]
+    #[case(crate::day10::problem, &[], include_str!("day10/example.7.txt"), &[None, Some("227")])]
+    #[case(crate::day11::problem, &[], include_str!("day11/example.1.txt"), &[Some("55312"), None])]
+    #[case(crate::day12::problem, &[], include_str!("day12/example.1.txt"), &[Some("772"), Some("436")])]
+    #[case(crate::day12::problem, &[], include_str!("day12/example.4.txt"), &[None, Some("368")])]
+    #[case(crate::day13::problem, &[], include_str!("day13/example.1.txt"), &[Some("480"), None])]
+    #[case(crate::day14::problem, &[("width", 11), ("height", 7)], include_str!("day14/example.1.txt"), &[Some("12"), None])]
+    #[case(crate::day15::problem, &[], include_str!("day15/example.1.txt"), &[Some("2028"), None])]
+    #[case(crate::day15::problem, &[], include_str!("day15/example.2.txt"), &[Some("10092"), Some("9021")])]
+    #[case(crate::day16::problem, &[], include_str!("day16/example.1.txt"), &[Some("7036"), Some("45")])]
+    #[case(crate::day17::problem, &[], include_str!("day17/example.1.txt"), &[Some("4,6,3,5,6,3,5,2,1,0"), None])]
+    #[case(crate::day18::problem, &[("time", 12), ("width", 7), ("height", 7)], include_str!("day18/example.1.txt"), &[Some("22"), Some("6,1")])]
+    #[case(crate::day19::problem, &[], include_str!("day19/example.1.txt"), &[Some("6"), Some("16")])]
+    #[case(crate::day20::problem, &[("threshold", 50)], include_str!("day20/example.1.txt"), &[Some("1"), Some("285")])]
+    #[case(crate::day21::problem, &[], include_str!("day21/example.1.txt"), &[Some("126384"), None])]
+    #[case(crate::day22::problem, &[], include_str!("day22/example.1.txt"), &[Some("37327623"), None])]
+    #[case(crate::day22::problem, &[], include_str!("day22/example.2.txt"), &[None, Some("23")])]
+    #[case(crate::day23::problem, &[], include_str!("day23/example.1.txt"), &[Some("7"), Some("co,de,ka,ta")])]
+    #[case(crate::day24::problem, &[], include_str!("day24/example.1.txt"), &[Some("4"), None])]
+    #[case(crate::day24::problem, &[], include_str!("day24/example.2.txt"), &[Some("2024"), None])]
+    #[case(crate::day25::problem, &[], include_str!("day25/example.1.txt"), &[Some("3")])]
+    fn test_examples(
+        #[case] problem: ProblemFn,
+        #[case] params: &[(&str, i64)],
+        #[case] example: &str,
+        #[case] answers: &[Option<&str>],
+        #[values(
+            Variant::Crlf,
+            Variant::Bom,
+            Variant::NoFinalNewline,
+            Variant::TrailingWhitespace,
+            Variant::TrailingBlankLines
+        )]
+        variant: Variant,
+    ) {
+        let expected = run(problem, params, example);
+        assert_eq!(expected.len(), answers.len());
+        for (part, (result, answer)) in expected.iter().zip(answers).enumerate() {
+            if let Some(answer) = answer {
+                assert_eq!(result.as_deref(), Ok(*answer), "Part {}", part + 1);
+            }
+        }
+
+        let result = run(problem, params, &variant.apply(example));
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_params() {
+        assert_eq!(param("width", 101u32).unwrap(), 101);
+
+        let params = BTreeMap::from([("width".to_owned(), 11), ("time".to_owned(), -1)]);
+        with_params(params, || {
+            assert_eq!(param("width", 101u32).unwrap(), 11);
+            assert_eq!(param("height", 103u32).unwrap(), 103);
+            assert!(param("time", 1024u32).is_err());
+        });
+
+        assert_eq!(param("width", 101u32).unwrap(), 101);
+    }
+}
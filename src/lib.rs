@@ -1,5 +1,23 @@
 pub const AOC_LOG: &str = "AOC_LOG";
 
+pub mod animate;
+pub mod answers;
+pub mod boundary;
+pub mod cache;
+pub mod cancel;
+pub mod config;
+pub mod dump;
+pub mod generate;
+pub mod input;
+pub mod parsed;
+pub mod plugin;
+pub mod progress;
+pub mod render;
+pub mod run;
+pub mod search;
+pub mod serve;
+pub mod variant;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
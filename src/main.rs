@@ -1,15 +1,34 @@
+use std::{
+    fmt::Display,
+    io::Read,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use advent_2024::{
+    answers::Answers,
+    config::Config,
+    plugin::Plugin,
+    run::{find_part, run_detached, run_part, PartResult},
+    variant::VariantCollection,
+};
 use aoc_utils::{
     harness::{Harness, Runner},
     problem::{Problem, ProblemCollection},
     AocError,
 };
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
-fn problems<H>(harness: H) -> ProblemCollection
+/// Timeout of the requests to `serve` unless one is configured.
+const DEFAULT_SERVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn problems<H>(harness: H, plugins: &[Plugin]) -> ProblemCollection
 where
     H: Harness,
 {
-    ProblemCollection::builder(harness)
+    let builder = ProblemCollection::builder(harness)
         .add_problem(1, advent_2024::day01::problem)
         .add_problem(2, advent_2024::day02::problem)
         .add_problem(3, advent_2024::day03::problem)
@@ -34,63 +53,779 @@ where
         .add_problem(22, advent_2024::day22::problem)
         .add_problem(23, advent_2024::day23::problem)
         .add_problem(24, advent_2024::day24::problem)
-        .add_problem(25, advent_2024::day25::problem)
+        .add_problem(25, advent_2024::day25::problem);
+
+    plugins
+        .iter()
+        .fold(builder, |builder, plugin| plugin.add_to(builder))
+        .build()
+}
+
+fn build_problems(time: bool, plugins: &[Plugin]) -> ProblemCollection {
+    if time {
+        problems(advent_2024::parsed::StageTimingHarness, plugins)
+    } else {
+        problems(aoc_utils::harness::SimpleHarness, plugins)
+    }
+}
+
+fn variants<H>(harness: H) -> VariantCollection
+where
+    H: Harness,
+{
+    VariantCollection::builder(harness)
+        .add_day(14, advent_2024::day14::variants)
+        .add_day(17, advent_2024::day17::variants)
         .build()
 }
 
+fn build_variants(time: bool) -> VariantCollection {
+    if time {
+        variants(advent_2024::parsed::StageTimingHarness)
+    } else {
+        variants(aoc_utils::harness::SimpleHarness)
+    }
+}
+
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
     aoc_utils::tracing::setup_tracing(advent_2024::AOC_LOG);
 
-    let problems = if args.time {
-        problems(aoc_utils::harness::TimingHarness)
-    } else {
-        problems(aoc_utils::harness::SimpleHarness)
+    let mut config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::load_or_default(Path::new(advent_2024::config::FILE_NAME))?,
     };
+    args.apply(&mut config);
+    config.validate()?;
 
-    if let Some(problem_num) = args.problem {
-        let problem = problems
-            .get(problem_num)
-            .ok_or(AocError::NoSuchProblem(problem_num))?;
+    if args.print_config {
+        print!("{}", config.to_toml()?);
+        return Ok(());
+    }
 
-        run_problem(problem_num, problem);
+    if let Some(Command::Generate { day, seed, size }) = args.command {
+        let seed = seed.unwrap_or_else(|| {
+            let seed = random_seed();
+            eprintln!("Seed: {seed}");
+            seed
+        });
+
+        // Generators read their parameters from the day's overrides, like the solvers
+        let input = advent_2024::input::with_params(config.params(day), || {
+            advent_2024::generate::generate(day, seed, size)
+        })?;
+        print!("{input}");
+        return Ok(());
+    }
+
+    if let Some(directory) = args.render {
+        advent_2024::render::set_output(directory, config.render_format)?;
+    }
+
+    if let Some(threads) = config.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()?;
+    }
+
+    advent_2024::progress::enable();
+
+    if args.animate {
+        advent_2024::animate::enable(advent_2024::animate::Settings {
+            fps: args.fps,
+            from_frame: args.from_frame,
+            every: args.every,
+        })?;
+    }
+
+    let plugins = match &config.plugins {
+        // SAFETY: the plugins directory is trusted as much as the binary itself
+        Some(directory) => unsafe { advent_2024::plugin::load_dir(directory)? },
+        None => Vec::new(),
+    };
+
+    let session = Session {
+        time: args.time,
+        timeout: config.timeout.map(Duration::from_secs_f64),
+        input: args.input.as_deref().map(read_input).transpose()?,
+        answers: match &config.answers {
+            Some(path) => Answers::load(path)?,
+            None => Answers::default(),
+        },
+        config,
+        plugins,
+        dump: args.dump.map(advent_2024::dump::Sink::new).transpose()?,
+    };
+
+    if let Some(Command::Batch {
+        day,
+        directory,
+        part,
+    }) = &args.command
+    {
+        let problems = build_problems(false, &session.plugins);
+        let problem = problems.get(*day).ok_or(AocError::NoSuchProblem(*day))?;
+        return session.run_batch(*day, problem, directory, *part);
+    }
+
+    if let Some(Command::Serve { port, max_body }) = args.command {
+        let options = advent_2024::serve::Options {
+            port,
+            max_body,
+            timeout: session.timeout.unwrap_or(DEFAULT_SERVE_TIMEOUT),
+            config: session.config,
+        };
+
+        let plugins = session.plugins;
+        return advent_2024::serve::serve(move || build_problems(false, &plugins), &options);
+    }
+
+    if args.cross_check {
+        let problems = build_problems(false, &session.plugins);
+        let variants = build_variants(false);
+        return session.cross_check(&problems, &variants, args.problem.as_ref());
+    }
+
+    let problems = build_problems(args.time, &session.plugins);
+    if let Some(selection) = &args.problem {
+        let variants = build_variants(args.time);
+        session.run_selection(&problems, &variants, selection)?;
     } else {
-        run_all(&problems);
+        session.run_all(&problems)?;
     }
 
     Ok(())
 }
 
-fn run_all(problems: &ProblemCollection) {
-    for (problem_num, problem) in problems.iter() {
+fn read_input(path: &Path) -> std::io::Result<String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        std::fs::read_to_string(path)
+    }
+}
+
+fn random_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+/// Reads `dayXX.txt` from the inputs directory, if there is one for the problem.
+fn read_day_input(directory: &Path, problem_num: i32) -> std::io::Result<Option<String>> {
+    match std::fs::read_to_string(directory.join(format!("day{problem_num:02}.txt"))) {
+        Ok(input) => Ok(Some(input)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+struct Session {
+    time: bool,
+    timeout: Option<Duration>,
+    /// Input given on the command line for the selected problem
+    input: Option<String>,
+    answers: Answers,
+    config: Config,
+    plugins: Vec<Plugin>,
+    /// Where the parts write their artifacts, if anywhere
+    dump: Option<advent_2024::dump::Sink>,
+}
+
+impl Session {
+    fn run_all(&self, problems: &ProblemCollection) -> anyhow::Result<()> {
+        for (problem_num, problem) in problems.iter() {
+            self.run_problem(problem_num, problem)?;
+        }
+
+        Ok(())
+    }
+
+    fn run_problem(&self, problem_num: i32, problem: &Problem) -> anyhow::Result<()> {
+        let input = self.problem_input(problem_num)?;
         for (part_idx, runner) in problem.iter() {
-            run_single(problem_num, part_idx, runner)
+            let target = Target::part(problem_num, part_idx);
+            self.run_single(target, runner, input.as_deref())?;
+        }
+
+        Ok(())
+    }
+
+    fn run_selection(
+        &self,
+        problems: &ProblemCollection,
+        variants: &VariantCollection,
+        selection: &Selection,
+    ) -> anyhow::Result<()> {
+        let problem_num = selection.problem_num;
+        let problem = problems
+            .get(problem_num)
+            .ok_or(AocError::NoSuchProblem(problem_num))?;
+        let input = self.problem_input(problem_num)?;
+
+        match (selection.part, &selection.variant) {
+            (Some(part), Some(name)) => {
+                let (name, runner) = variants
+                    .get(problem_num, part)
+                    .find(|&(variant, _)| variant == name)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Problem {problem_num}-{part} has no variant '{name}'")
+                    })?;
+
+                let target = Target {
+                    variant: Some(name),
+                    ..Target::part(problem_num, part - 1)
+                };
+                self.run_single(target, runner, input.as_deref())
+            }
+            (Some(part), None) => {
+                let runner = find_part(problems, problem_num, part.wrapping_sub(1))
+                    .ok_or_else(|| anyhow::anyhow!("Problem {problem_num} has no part {part}"))?;
+
+                let target = Target::part(problem_num, part - 1);
+                self.run_single(target, runner, input.as_deref())
+            }
+            (None, _) => self.run_problem(problem_num, problem),
+        }
+    }
+
+    /// Input given on the command line or in the inputs directory for a problem, if any.
+    fn problem_input(&self, problem_num: i32) -> std::io::Result<Option<String>> {
+        match (&self.input, &self.config.inputs) {
+            (Some(input), _) => Ok(Some(input.clone())),
+            (None, Some(directory)) => read_day_input(directory, problem_num),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn run_single(
+        &self,
+        target: Target,
+        runner: &dyn Runner,
+        input: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let result = self.run(target, runner, input)?;
+
+        print!("Problem {target}: ");
+        match result {
+            Some(Ok(Ok(out))) => match self.answers.get(target.problem_num, target.part_idx + 1) {
+                Some(expected) if !Answers::matches(expected, &out) => {
+                    println!("{out} (WRONG, expected {expected})")
+                }
+                _ => println!("{out}"),
+            },
+            Some(Ok(Err(e))) => println!("{e}"),
+            Some(Err(panic)) => println!("{panic}"),
+            None => println!("TIMEOUT after {:?}", self.timeout.unwrap_or_default()),
+        }
+
+        Ok(())
+    }
+
+    /// Runs every part of a problem over each input file in `directory`, and prints a table of
+    /// the results. Inputs are checked against a sibling `.answers` file if there is one.
+    fn run_batch(
+        &self,
+        problem_num: i32,
+        problem: &Problem,
+        directory: &Path,
+        part: Option<usize>,
+    ) -> anyhow::Result<()> {
+        let parts = problem
+            .iter()
+            .filter(|&(part_idx, _)| part.is_none_or(|part| part == part_idx + 1))
+            .collect::<Vec<_>>();
+
+        if parts.is_empty() {
+            anyhow::bail!(
+                "Problem {problem_num} has no part {}",
+                part.unwrap_or_default()
+            );
+        }
+
+        let mut paths = std::fs::read_dir(directory)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        paths.retain(|path| path.is_file() && path.extension() != Some("answers".as_ref()));
+        paths.sort();
+
+        let mut header = vec!["File".to_owned()];
+        for &(part_idx, _) in parts.iter() {
+            header.push(format!("Part {}", part_idx + 1));
+            header.push("Time".to_owned());
+        }
+        header.push("Notes".to_owned());
+
+        let mut rows = Vec::new();
+        let mut flagged = 0;
+        for path in paths {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            let mut row = vec![name];
+            let mut notes = Vec::new();
+            match self.run_batch_file(problem_num, &parts, &path, &mut row, &mut notes) {
+                Ok(()) => {}
+                Err(e) => notes.push(format!("{e:#}")),
+            }
+
+            if !notes.is_empty() {
+                flagged += 1;
+            }
+
+            row.resize(header.len() - 1, String::new());
+            row.push(notes.join("; "));
+            rows.push(row);
+        }
+
+        print_table(&header, &rows);
+
+        if flagged > 0 {
+            anyhow::bail!("{flagged} of {} inputs flagged", rows.len());
+        }
+
+        Ok(())
+    }
+
+    fn run_batch_file(
+        &self,
+        problem_num: i32,
+        parts: &[(usize, &dyn Runner)],
+        path: &Path,
+        row: &mut Vec<String>,
+        notes: &mut Vec<String>,
+    ) -> anyhow::Result<()> {
+        let input = std::fs::read_to_string(path)?;
+
+        let answers_path = path.with_extension("answers");
+        let answers = if answers_path.exists() {
+            Answers::load(&answers_path)?
+        } else {
+            Answers::default()
+        };
+
+        for &(part_idx, runner) in parts {
+            let part_num = part_idx + 1;
+
+            let start = Instant::now();
+            let target = Target::part(problem_num, part_idx);
+            let result = self.run(target, runner, Some(&input))?;
+            let elapsed = start.elapsed();
+
+            let cell = match result {
+                Some(Ok(Ok(out))) => {
+                    match answers.get(problem_num, part_num) {
+                        Some(expected) if !Answers::matches(expected, &out) => {
+                            notes.push(format!("part {part_num} expected {expected}"));
+                        }
+                        _ => {}
+                    }
+
+                    out
+                }
+                Some(Ok(Err(e))) => {
+                    notes.push(format!("part {part_num}: {e}"));
+                    "ERROR".to_owned()
+                }
+                Some(Err(panic)) => {
+                    notes.push(format!("part {part_num}: {panic}"));
+                    "PANIC".to_owned()
+                }
+                None => {
+                    notes.push(format!("part {part_num} timed out"));
+                    "TIMEOUT".to_owned()
+                }
+            };
+
+            row.push(cell);
+            row.push(format!("{elapsed:.2?}"));
+        }
+
+        Ok(())
+    }
+
+    /// Runs every variant of the selected parts next to the registered solution, and prints a
+    /// table of their answers and timings.
+    fn cross_check(
+        &self,
+        problems: &ProblemCollection,
+        variants: &VariantCollection,
+        selection: Option<&Selection>,
+    ) -> anyhow::Result<()> {
+        let header = ["Part", "Variant", "Answer", "Time", "Notes"].map(str::to_owned);
+
+        let mut rows = Vec::new();
+        let mut checked = 0;
+        let mut disagreeing = 0;
+        for (problem_num, part) in variants.parts() {
+            if selection.is_some_and(|selection| {
+                selection.problem_num != problem_num
+                    || selection.part.is_some_and(|selected| selected != part)
+            }) {
+                continue;
+            }
+
+            let input = self.problem_input(problem_num)?;
+
+            let mut targets = Vec::new();
+            if let Some(runner) = find_part(problems, problem_num, part - 1) {
+                targets.push((Target::part(problem_num, part - 1), runner));
+            }
+            for (name, runner) in variants.get(problem_num, part) {
+                let target = Target {
+                    variant: Some(name),
+                    ..Target::part(problem_num, part - 1)
+                };
+                targets.push((target, runner));
+            }
+
+            let mut answers = Vec::new();
+            for (target, runner) in targets {
+                let start = Instant::now();
+                let result = self.run(target, runner, input.as_deref())?;
+                let elapsed = start.elapsed();
+
+                let mut notes = Vec::new();
+                let (cell, answer) = match result {
+                    Some(Ok(Ok(out))) => {
+                        match self.answers.get(problem_num, part) {
+                            Some(expected) if !Answers::matches(expected, &out) => {
+                                notes.push(format!("expected {expected}"));
+                            }
+                            _ => {}
+                        }
+
+                        (out.clone(), Some(out))
+                    }
+                    Some(Ok(Err(e))) => {
+                        notes.push(e.to_string());
+                        ("ERROR".to_owned(), None)
+                    }
+                    Some(Err(panic)) => {
+                        notes.push(panic.to_string());
+                        ("PANIC".to_owned(), None)
+                    }
+                    None => {
+                        notes.push("timed out".to_owned());
+                        ("TIMEOUT".to_owned(), None)
+                    }
+                };
+
+                rows.push(vec![
+                    format!("{problem_num}-{part}"),
+                    target.variant.unwrap_or("default").to_owned(),
+                    cell,
+                    format!("{elapsed:.2?}"),
+                    notes.join("; "),
+                ]);
+                answers.push(answer);
+            }
+
+            checked += 1;
+            if answers
+                .iter()
+                .any(|answer| answer.is_none() || *answer != answers[0])
+            {
+                disagreeing += 1;
+            }
+        }
+
+        if checked == 0 {
+            anyhow::bail!("No variants to cross-check");
+        }
+
+        print_table(&header, &rows);
+
+        if disagreeing > 0 {
+            anyhow::bail!("{disagreeing} of {checked} parts disagree");
+        }
+
+        Ok(())
+    }
+
+    fn run(
+        &self,
+        target: Target,
+        runner: &dyn Runner,
+        input: Option<&str>,
+    ) -> std::io::Result<Option<PartResult>> {
+        let params = self.config.params(target.problem_num);
+        match self.timeout {
+            Some(timeout) => {
+                let time = self.time;
+                let plugins = self.plugins.clone();
+                let input = input.map(str::to_owned);
+                let dump = self.dump.clone();
+                run_detached(format!("problem-{target}"), timeout, move || {
+                    let problems;
+                    let variants;
+                    let runner = match target.variant {
+                        Some(name) => {
+                            variants = build_variants(time);
+                            variants.find(target.problem_num, target.part_idx + 1, name)
+                        }
+                        None => {
+                            problems = build_problems(time, &plugins);
+                            find_part(&problems, target.problem_num, target.part_idx)
+                        }
+                    }
+                    .expect("Part should exist in every collection");
+
+                    run_part(runner, input.as_deref(), params, dump)
+                })
+            }
+            None => Ok(Some(run_part(runner, input, params, self.dump.clone()))),
+        }
+    }
+}
+
+/// A part or one of its variants, which detached runs look up again in collections of their own.
+#[derive(Debug, Clone, Copy)]
+struct Target {
+    problem_num: i32,
+    part_idx: usize,
+    variant: Option<&'static str>,
+}
+
+impl Target {
+    fn part(problem_num: i32, part_idx: usize) -> Self {
+        Self {
+            problem_num,
+            part_idx,
+            variant: None,
         }
     }
 }
 
-fn run_problem(problem_num: i32, problem: &Problem) {
-    for (part_idx, runner) in problem.iter() {
-        run_single(problem_num, part_idx, runner)
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.problem_num, self.part_idx + 1)?;
+        if let Some(variant) = self.variant {
+            write!(f, "@{variant}")?;
+        }
+
+        Ok(())
     }
 }
 
-fn run_single(problem_num: i32, part_idx: usize, runner: &dyn Runner) {
-    let result = runner.run();
-    print!("Problem {}-{}: ", problem_num, part_idx + 1);
-    match result {
-        Ok(out) => println!("{out}"),
-        Err(e) => println!("{e}"),
+/// What to run, given as `DAY[:PART][@VARIANT]` on the command line.
+#[derive(Debug, Clone)]
+struct Selection {
+    problem_num: i32,
+    part: Option<usize>,
+    variant: Option<String>,
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{s}' isn't a problem, e.g. `14`, `14:2` or `14:2@crt`");
+
+        let (rest, variant) = match s.split_once('@') {
+            Some((rest, variant)) if !variant.is_empty() => (rest, Some(variant.to_owned())),
+            Some(_) => return Err(invalid()),
+            None => (s, None),
+        };
+
+        let (problem_num, part) = match rest.split_once(':') {
+            Some((problem_num, part)) => (problem_num, Some(part.parse().map_err(|_| invalid())?)),
+            None if variant.is_none() => (rest, None),
+            None => return Err(invalid()),
+        };
+
+        Ok(Self {
+            problem_num: problem_num.parse().map_err(|_| invalid())?,
+            part,
+            variant,
+        })
+    }
+}
+
+fn print_table(header: &[String], rows: &[Vec<String>]) {
+    let mut widths = header
+        .iter()
+        .map(|cell| cell.chars().count())
+        .collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |row: &[String]| {
+        let line = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, &width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    };
+
+    print_row(header);
+    let separator = widths
+        .iter()
+        .map(|&width| "-".repeat(width))
+        .collect::<Vec<_>>();
+    print_row(&separator);
+    for row in rows {
+        print_row(row);
     }
 }
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Read runner defaults from this file instead of `advent.toml`
+    #[arg(long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+
+    /// Print the resolved configuration and exit
+    #[arg(long)]
+    print_config: bool,
+
     /// Time the solutions
     #[arg(long)]
     time: bool,
 
-    /// Selects what problem to run
-    problem: Option<i32>,
+    /// Read the problem's input from this file instead of the embedded one (`-` for stdin)
+    #[arg(long, value_name = "FILE", requires = "problem")]
+    input: Option<PathBuf>,
+
+    /// Read `dayXX.txt` inputs from this directory instead of the embedded ones
+    #[arg(long, value_name = "DIR")]
+    inputs: Option<PathBuf>,
+
+    /// Load extra days from the shared libraries in this directory
+    #[arg(long, value_name = "DIR", global = true)]
+    plugins: Option<PathBuf>,
+
+    /// Check results against the expected answers in this file
+    #[arg(long, value_name = "FILE")]
+    answers: Option<PathBuf>,
+
+    /// Give up on a part after this many seconds
+    #[arg(long, value_name = "SECONDS", global = true)]
+    timeout: Option<f64>,
+
+    /// Number of threads used by parallel solutions
+    #[arg(long, value_name = "N", global = true)]
+    threads: Option<NonZeroUsize>,
+
+    /// Write grid snapshots and other visual outputs to this directory
+    #[arg(long, value_name = "DIR")]
+    render: Option<PathBuf>,
+
+    /// Image format of rendered grids
+    #[arg(long, value_enum)]
+    render_format: Option<advent_2024::render::Format>,
+
+    /// Write the solvers' intermediate structures to this directory as JSON
+    #[arg(long, value_name = "DIR")]
+    dump: Option<PathBuf>,
+
+    /// Play simulations back in the terminal
+    #[arg(long)]
+    animate: bool,
+
+    /// Animation playback speed, in frames per second
+    #[arg(long, requires = "animate", default_value_t = 30.0)]
+    fps: f64,
+
+    /// Skip animation frames before this one
+    #[arg(long, value_name = "FRAME", requires = "animate", default_value_t = 0)]
+    from_frame: usize,
+
+    /// Only draw every Nth animation frame
+    #[arg(long, value_name = "N", requires = "animate", default_value = "1")]
+    every: NonZeroUsize,
+
+    /// Run every variant of the selected parts and check that they agree
+    #[arg(long, conflicts_with = "time")]
+    cross_check: bool,
+
+    /// Selects what to run: a problem, one of its parts, or a variant of the part, e.g. `14`,
+    /// `14:2` or `14:2@crt`
+    problem: Option<Selection>,
+}
+
+impl Args {
+    /// Overrides the configuration with the flags given on the command line.
+    fn apply(&self, config: &mut Config) {
+        if let Some(inputs) = &self.inputs {
+            config.inputs = Some(inputs.clone());
+        }
+
+        if let Some(answers) = &self.answers {
+            config.answers = Some(answers.clone());
+        }
+
+        if let Some(plugins) = &self.plugins {
+            config.plugins = Some(plugins.clone());
+        }
+
+        if let Some(timeout) = self.timeout {
+            config.timeout = Some(timeout);
+        }
+
+        if let Some(threads) = self.threads {
+            config.threads = Some(threads.get());
+        }
+
+        if let Some(format) = self.render_format {
+            config.render_format = format;
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print a random input for a problem
+    Generate {
+        /// Problem to generate an input for
+        day: i32,
+
+        /// Seed of the generator, random by default
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Scale of the input, interpreted by each problem
+        #[arg(long, default_value_t = 50)]
+        size: usize,
+    },
+
+    /// Run a problem over every input in a directory, checking them against sibling
+    /// `.answers` files
+    Batch {
+        /// Problem to run
+        day: i32,
+
+        /// Directory of inputs
+        directory: PathBuf,
+
+        /// Only run this part
+        #[arg(long)]
+        part: Option<usize>,
+    },
+
+    /// Serve a JSON API over the solvers on localhost
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8024)]
+        port: u16,
+
+        /// Largest request body accepted, in bytes
+        #[arg(long, value_name = "BYTES", default_value_t = 1 << 20)]
+        max_body: usize,
+    },
 }
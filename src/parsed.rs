@@ -0,0 +1,218 @@
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use aoc_utils::{
+    harness::{Harness, TimingHarness},
+    problem::ProblemBuilder,
+};
+
+thread_local! {
+    /// The last parse done by a [`Stage`] on this thread.
+    static LAST_PARSE: Cell<Option<ParseTime>> = const { Cell::new(None) };
+}
+
+/// Time spent parsing, or `None` if the input had already been parsed by another part.
+type ParseTime = Option<Duration>;
+
+/// Adds parts that borrow the day's input parsed once, rather than parsing it themselves.
+///
+/// ```ignore
+/// builder
+///     .parse_input(INPUT, parse)
+///     .add_part(part_1)
+///     .add_part(|map| part_2(map, input::param("threshold", 100)?));
+/// ```
+pub trait ParseStage<H> {
+    /// Starts a parse stage reading the input selected by the runner, or `default`, with `parse`.
+    fn parse_input<T>(
+        &mut self,
+        default: &'static str,
+        parse: fn(&str) -> anyhow::Result<T>,
+    ) -> ParsedParts<'_, H, T>;
+}
+
+impl<H> ParseStage<H> for ProblemBuilder<H>
+where
+    H: Harness,
+{
+    fn parse_input<T>(
+        &mut self,
+        default: &'static str,
+        parse: fn(&str) -> anyhow::Result<T>,
+    ) -> ParsedParts<'_, H, T> {
+        ParsedParts {
+            builder: self,
+            stage: Arc::new(Stage {
+                default,
+                parse,
+                last: Mutex::new(None),
+            }),
+        }
+    }
+}
+
+/// Parts of a problem sharing a parse stage, see [`ParseStage`].
+pub struct ParsedParts<'a, H, T> {
+    builder: &'a mut ProblemBuilder<H>,
+    stage: Arc<Stage<T>>,
+}
+
+impl<H, T> ParsedParts<'_, H, T>
+where
+    H: Harness,
+    T: Send + Sync + 'static,
+{
+    /// Adds a part solving the parsed input.
+    pub fn add_part<F, R>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(&T) -> anyhow::Result<R> + Send + Sync + 'static,
+        R: Display + 'static,
+    {
+        let stage = self.stage.clone();
+        self.builder.add_part(move || f(&*stage.get()?));
+        self
+    }
+}
+
+/// The input of a problem, parsed once and shared by its parts.
+///
+/// Each collection of problems has its own stages, so detached runs, which build their own
+/// collection, parse again.
+struct Stage<T> {
+    default: &'static str,
+    parse: fn(&str) -> anyhow::Result<T>,
+    last: Mutex<Option<(String, Arc<T>)>>,
+}
+
+impl<T> Stage<T> {
+    /// Parses the selected input, or returns what the last call parsed if it was given the same
+    /// input.
+    ///
+    /// Errors aren't kept, so a part given invalid input reports the error again.
+    fn get(&self) -> anyhow::Result<Arc<T>> {
+        let start = Instant::now();
+        let input = crate::input::get(self.default);
+
+        let last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, parsed)) = last.as_ref().filter(|(last, _)| *last == input) {
+            LAST_PARSE.set(Some(None));
+            return Ok(parsed.clone());
+        }
+        drop(last);
+
+        // Not locked while parsing, so a part that timed out doesn't hold up the next ones
+        let parsed = Arc::new((self.parse)(&input)?);
+        LAST_PARSE.set(Some(Some(start.elapsed())));
+
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        *last = Some((Cow::into_owned(input), parsed.clone()));
+
+        Ok(parsed)
+    }
+}
+
+/// Times parts like [`TimingHarness`], reporting the time spent parsing apart from the time
+/// spent solving for the parts added through a [`ParseStage`].
+#[derive(Debug, Clone, Copy)]
+pub struct StageTimingHarness;
+
+/// Forgets the parse recorded on this thread, which a part that failed after parsing leaves
+/// behind, before running another part.
+pub fn clear_parse_time() {
+    LAST_PARSE.set(None);
+}
+
+impl Harness for StageTimingHarness {
+    fn wrap(&self, elapsed: Duration, output: String) -> String {
+        match LAST_PARSE.take() {
+            Some(Some(parse)) => format!(
+                "{output} (parse: {parse:?}, solve: {:?})",
+                elapsed.saturating_sub(parse)
+            ),
+            Some(None) => format!("{output} (parse: shared, solve: {elapsed:?})"),
+            None => TimingHarness.wrap(elapsed, output),
+        }
+    }
+
+    fn boxed(&self) -> Box<dyn Harness + Send + Sync> {
+        Box::new(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use aoc_utils::problem::ProblemCollection;
+
+    use super::*;
+
+    static PARSES: AtomicUsize = AtomicUsize::new(0);
+
+    fn parse(input: &str) -> anyhow::Result<Vec<u32>> {
+        PARSES.fetch_add(1, Ordering::Relaxed);
+        Ok(input
+            .trim_end()
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn problem<H: Harness>(builder: &mut ProblemBuilder<H>) {
+        builder
+            .parse_input("1,2,3\n", parse)
+            .add_part(|values| Ok(values.iter().sum::<u32>()))
+            .add_part(|values| Ok(values.len()));
+    }
+
+    fn run(problems: &ProblemCollection) -> Vec<anyhow::Result<String>> {
+        let problem = problems.get(1).unwrap();
+        problem.iter().map(|(_, runner)| runner.run()).collect()
+    }
+
+    #[test]
+    fn test_parse_stage() {
+        let problems = ProblemCollection::builder(StageTimingHarness)
+            .add_problem(1, problem)
+            .build();
+        let answers = |outputs: Vec<anyhow::Result<String>>| {
+            outputs
+                .into_iter()
+                .map(|output| output.map_err(|e| e.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        let parses = PARSES.load(Ordering::Relaxed);
+        let outputs = answers(run(&problems));
+        assert_eq!(PARSES.load(Ordering::Relaxed), parses + 1);
+        assert!(outputs[0].as_ref().unwrap().starts_with("6 (parse: "));
+        assert!(outputs[1]
+            .as_ref()
+            .unwrap()
+            .starts_with("3 (parse: shared, solve: "));
+
+        // Another input is parsed again, and invalid ones every time
+        let outputs = crate::input::with_override("4", || answers(run(&problems)));
+        assert!(outputs[0].as_ref().unwrap().starts_with("4 (parse: "));
+        crate::input::with_override("x", || {
+            assert!(run(&problems).iter().all(Result::is_err));
+        });
+        assert_eq!(PARSES.load(Ordering::Relaxed), parses + 4);
+    }
+
+    #[test]
+    fn test_stage_timing_harness() {
+        // Nothing was parsed for this part
+        clear_parse_time();
+        let output = StageTimingHarness.wrap(Duration::from_millis(1), "4".to_owned());
+        assert_eq!(
+            output,
+            TimingHarness.wrap(Duration::from_millis(1), "4".to_owned())
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::{
+    collections::BTreeMap,
+    ffi::c_void,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context;
+use aoc_utils::{
+    harness::Harness,
+    problem::{ProblemBuilder, ProblemCollectionBuilder},
+    AocError,
+};
+use libloading::Library;
+
+use crate::input;
+
+/// Version of the interface below, bumped whenever it changes.
+pub const ABI_VERSION: u32 = 1;
+
+/// Symbol of the [`EntryPoint`] every plugin exports.
+pub const ENTRY_POINT: &str = "advent_2024_register";
+
+/// Appends the `len` bytes at `data` to `out`.
+pub type Write = unsafe extern "C" fn(out: *mut c_void, data: *const u8, len: usize);
+
+/// Solves a part for the UTF-8 `input`, writing the answer to `out`, or the error message if it
+/// returns non-zero.
+pub type Solve =
+    unsafe extern "C" fn(input: *const u8, input_len: usize, out: *mut c_void, write: Write) -> i32;
+
+/// Adds the plugin's parts to the runner.
+///
+/// Returns zero, or non-zero if the plugin doesn't support the registrar's `abi_version`.
+pub type EntryPoint = unsafe extern "C" fn(registrar: *mut Registrar) -> i32;
+
+#[repr(C)]
+pub struct Registrar {
+    pub abi_version: u32,
+    pub context: *mut c_void,
+    /// Adds `solve` as the next part of `day`, which replaces the built-in day if there is one.
+    pub add_part: unsafe extern "C" fn(context: *mut c_void, day: i32, solve: Solve),
+}
+
+/// Days added by a shared library.
+#[derive(Clone)]
+pub struct Plugin {
+    path: PathBuf,
+    days: BTreeMap<i32, Vec<Solve>>,
+    /// Keeps the library loaded while its parts can still be called
+    library: Option<Arc<Library>>,
+}
+
+impl Plugin {
+    /// Loads the plugin at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The library runs arbitrary code when loaded, and must export an [`EntryPoint`] that
+    /// follows this interface. Its functions must not unwind.
+    pub unsafe fn load(path: &Path) -> anyhow::Result<Self> {
+        let library = Library::new(path)?;
+        let entry = *library.get::<EntryPoint>(ENTRY_POINT.as_bytes())?;
+
+        Self::register(path, entry, Some(Arc::new(library)))
+    }
+
+    unsafe fn register(
+        path: &Path,
+        entry: EntryPoint,
+        library: Option<Arc<Library>>,
+    ) -> anyhow::Result<Self> {
+        unsafe extern "C" fn add_part(context: *mut c_void, day: i32, solve: Solve) {
+            let days = &mut *context.cast::<BTreeMap<i32, Vec<Solve>>>();
+            days.entry(day).or_default().push(solve);
+        }
+
+        let mut days = BTreeMap::new();
+        let mut registrar = Registrar {
+            abi_version: ABI_VERSION,
+            context: (&mut days as *mut BTreeMap<i32, Vec<Solve>>).cast(),
+            add_part,
+        };
+
+        if entry(&mut registrar) != 0 {
+            anyhow::bail!("Plugin doesn't support ABI version {ABI_VERSION}");
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+            days,
+            library,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = i32> + '_ {
+        self.days.keys().copied()
+    }
+
+    /// Adds the plugin's days to `builder`, replacing the ones it already has.
+    pub fn add_to<H>(&self, mut builder: ProblemCollectionBuilder<H>) -> ProblemCollectionBuilder<H>
+    where
+        H: Harness,
+    {
+        for (&day, parts) in &self.days {
+            builder = builder.add_problem(day, |problem: &mut ProblemBuilder<H>| {
+                for &solve in parts {
+                    let library = self.library.clone();
+                    problem.add_part(move || {
+                        let _library = &library;
+
+                        // Plugin days have no embedded input to fall back on
+                        let input = input::get("");
+                        if input.is_empty() {
+                            return Err(AocError::EmptyInput.into());
+                        }
+
+                        // SAFETY: `library` is still loaded, `load` covers the rest
+                        unsafe { call(solve, &input) }
+                    });
+                }
+            });
+        }
+
+        builder
+    }
+}
+
+/// Loads every shared library in `directory`, in the order of their names.
+///
+/// # Safety
+///
+/// See [`Plugin::load`].
+pub unsafe fn load_dir(directory: &Path) -> anyhow::Result<Vec<Plugin>> {
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(directory)
+        .with_context(|| format!("Unable to read {}", directory.display()))?
+    {
+        let path = entry?.path();
+        if path.extension() == Some(std::env::consts::DLL_EXTENSION.as_ref()) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let plugin = Plugin::load(path)
+                .with_context(|| format!("Unable to load plugin {}", path.display()))?;
+            let days = plugin.days().collect::<Vec<_>>();
+            tracing::info!(path = %path.display(), ?days, "Loaded plugin");
+            Ok(plugin)
+        })
+        .collect()
+}
+
+unsafe fn call(solve: Solve, input: &str) -> anyhow::Result<String> {
+    unsafe extern "C" fn write(out: *mut c_void, data: *const u8, len: usize) {
+        if len > 0 {
+            let out = &mut *out.cast::<Vec<u8>>();
+            out.extend_from_slice(std::slice::from_raw_parts(data, len));
+        }
+    }
+
+    let mut out = Vec::<u8>::new();
+    let status = solve(
+        input.as_ptr(),
+        input.len(),
+        (&mut out as *mut Vec<u8>).cast(),
+        write,
+    );
+    let out = String::from_utf8_lossy(&out).into_owned();
+
+    if status == 0 {
+        Ok(out)
+    } else {
+        Err(AocError::message(out).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::{harness::SimpleHarness, problem::ProblemCollection};
+
+    use super::*;
+
+    unsafe extern "C" fn count_lines(
+        input: *const u8,
+        input_len: usize,
+        out: *mut c_void,
+        write: Write,
+    ) -> i32 {
+        let input = std::slice::from_raw_parts(input, input_len);
+        let answer = input
+            .split(|&b| b == b'\n')
+            .filter(|l| !l.is_empty())
+            .count();
+        let answer = answer.to_string();
+        write(out, answer.as_ptr(), answer.len());
+        0
+    }
+
+    unsafe extern "C" fn fail(_: *const u8, _: usize, out: *mut c_void, write: Write) -> i32 {
+        let message = "not solved yet";
+        write(out, message.as_ptr(), message.len());
+        1
+    }
+
+    unsafe extern "C" fn entry(registrar: *mut Registrar) -> i32 {
+        let registrar = &*registrar;
+        if registrar.abi_version != ABI_VERSION {
+            return 1;
+        }
+
+        (registrar.add_part)(registrar.context, 1, count_lines);
+        (registrar.add_part)(registrar.context, 30, count_lines);
+        (registrar.add_part)(registrar.context, 30, fail);
+        0
+    }
+
+    unsafe extern "C" fn entry_unsupported(_: *mut Registrar) -> i32 {
+        1
+    }
+
+    #[test]
+    fn test_add_to() {
+        let plugin = unsafe { Plugin::register(Path::new("test"), entry, None) }.unwrap();
+        assert_eq!(plugin.days().collect::<Vec<_>>(), vec![1, 30]);
+
+        let builder = ProblemCollection::builder(SimpleHarness)
+            .add_problem(1, crate::day01::problem)
+            .add_problem(2, crate::day02::problem);
+        let problems = plugin.add_to(builder).build();
+
+        let parts = |day| problems.get(day).unwrap().iter().count();
+        assert_eq!((parts(1), parts(2), parts(30)), (1, 2, 2));
+
+        let results = input::with_override("a\nb\nc\n", || {
+            problems
+                .get(30)
+                .unwrap()
+                .iter()
+                .map(|(_, runner)| runner.run().map_err(|e| e.to_string()))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(results[0], Ok("3".to_owned()));
+        assert!(results[1].as_ref().unwrap_err().contains("not solved yet"));
+    }
+
+    #[test]
+    fn test_unsupported() {
+        assert!(unsafe { Plugin::register(Path::new("test"), entry_unsupported, None) }.is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+static STYLE: OnceLock<Style> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    /// A bar redrawn in place on stderr
+    Bar,
+    /// Periodic tracing events, for logs
+    Events,
+}
+
+impl Style {
+    fn interval(&self) -> Duration {
+        match self {
+            Self::Bar => Duration::from_millis(100),
+            Self::Events => Duration::from_secs(2),
+        }
+    }
+}
+
+/// Enables progress reporting for every [`Progress`] created afterwards, as a bar if stderr is a
+/// TTY and as tracing events otherwise.
+pub fn enable() {
+    let style = if io::stderr().is_terminal() {
+        Style::Bar
+    } else {
+        Style::Events
+    };
+
+    let _ = STYLE.set(style);
+}
+
+/// Counts the items a long-running loop has done, and reports them every so often.
+///
+/// Nothing is reported before the first interval has passed, so quick runs stay quiet.
+#[derive(Debug)]
+pub struct Progress {
+    name: &'static str,
+    total: u64,
+    done: AtomicU64,
+    style: Option<Style>,
+    start: Instant,
+    last_report: Mutex<Instant>,
+    drawn: AtomicBool,
+    /// Token of the part that created this, checked from whichever thread adds to it
+    cancel: Option<crate::cancel::Token>,
+}
+
+impl Progress {
+    pub fn new(name: &'static str, total: u64) -> Self {
+        let start = Instant::now();
+        Self {
+            name,
+            total,
+            done: AtomicU64::new(0),
+            style: STYLE.get().copied(),
+            start,
+            last_report: Mutex::new(start),
+            drawn: AtomicBool::new(false),
+            cancel: crate::cancel::current(),
+        }
+    }
+
+    /// Records one more item done, from any thread.
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, count: u64) {
+        if let Some(cancel) = &self.cancel {
+            cancel.check();
+        }
+
+        let done = self.done.fetch_add(count, Ordering::Relaxed) + count;
+
+        let Some(style) = self.style else {
+            return;
+        };
+
+        // Whoever holds the lock is already reporting
+        let Ok(mut last_report) = self.last_report.try_lock() else {
+            return;
+        };
+
+        if last_report.elapsed() < style.interval() {
+            return;
+        }
+        *last_report = Instant::now();
+
+        match style {
+            Style::Bar => {
+                let line = format_bar(self.name, done, self.total, self.start.elapsed());
+                let _ = write!(io::stderr(), "\r\x1b[2K{line}");
+                self.drawn.store(true, Ordering::Relaxed);
+            }
+            Style::Events => tracing::info!(name = self.name, done, total = self.total, "Progress"),
+        }
+    }
+
+    pub fn done(&self) -> u64 {
+        self.done.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if self.drawn.load(Ordering::Relaxed) {
+            let _ = write!(io::stderr(), "\r\x1b[2K");
+        }
+    }
+}
+
+fn format_bar(name: &str, done: u64, total: u64, elapsed: Duration) -> String {
+    const WIDTH: u64 = 30;
+
+    let fraction = if total == 0 {
+        1.0
+    } else {
+        (done.min(total) as f64) / total as f64
+    };
+    let filled = (fraction * WIDTH as f64) as usize;
+
+    format!(
+        "{name} [{}{}] {done}/{total} {:.0}% {elapsed:.1?}",
+        "#".repeat(filled),
+        ".".repeat(WIDTH as usize - filled),
+        fraction * 100.0,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bar() {
+        assert_eq!(
+            format_bar("day06", 250, 1000, Duration::from_millis(1500)),
+            "day06 [#######.......................] 250/1000 25% 1.5s"
+        );
+        assert_eq!(
+            format_bar("empty", 0, 0, Duration::ZERO),
+            "empty [##############################] 0/0 100% 0.0ns"
+        );
+    }
+
+    #[test]
+    fn test_progress() {
+        let progress = Progress::new("test", 10);
+        (0..10).for_each(|_| progress.inc());
+        progress.add(5);
+        assert_eq!(progress.done(), 15);
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+use aoc_utils::grid::Grid;
+
+/// Side length, in pixels, of a single grid cell in raster images.
+const CELL_SIZE: usize = 4;
+
+static RENDERER: OnceLock<Renderer> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Self = Self(0, 0, 0);
+    pub const WHITE: Self = Self(255, 255, 255);
+    pub const GREY: Self = Self(96, 96, 96);
+    pub const RED: Self = Self(220, 50, 47);
+    pub const GREEN: Self = Self(64, 200, 64);
+    pub const BLUE: Self = Self(38, 139, 210);
+    pub const ORANGE: Self = Self(203, 120, 22);
+    pub const YELLOW: Self = Self(230, 200, 40);
+
+    pub const fn luminance(&self) -> u8 {
+        let Self(r, g, b) = *self;
+        ((299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000) as u8
+    }
+}
+
+impl std::fmt::Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+pub trait Palette<T> {
+    fn color(&self, tile: &T) -> Rgb;
+}
+
+impl<T, F> Palette<T> for F
+where
+    F: Fn(&T) -> Rgb,
+{
+    fn color(&self, tile: &T) -> Rgb {
+        self(tile)
+    }
+}
+
+/// Palette for grids of display characters, as produced by the `DisplayChar` maps.
+pub fn char_palette(tile: &char) -> Rgb {
+    match tile {
+        '#' => Rgb::GREY,
+        'O' | '[' | ']' => Rgb::ORANGE,
+        '@' | '^' | '>' | 'v' | '<' => Rgb::RED,
+        'S' | 'E' => Rgb::GREEN,
+        'X' | '*' => Rgb::YELLOW,
+        ' ' | '.' => Rgb::BLACK,
+        _ => Rgb::WHITE,
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    Ppm,
+    Pgm,
+    Svg,
+}
+
+impl Format {
+    pub const fn extension(&self) -> &'static str {
+        match self {
+            Self::Ppm => "ppm",
+            Self::Pgm => "pgm",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+pub fn write<T>(
+    writer: &mut impl Write,
+    format: Format,
+    grid: &Grid<T>,
+    palette: &impl Palette<T>,
+) -> io::Result<()> {
+    match format {
+        Format::Ppm => write_ppm(writer, grid, palette),
+        Format::Pgm => write_pgm(writer, grid, palette),
+        Format::Svg => write_svg(writer, grid, palette),
+    }
+}
+
+pub fn write_ppm<T>(
+    writer: &mut impl Write,
+    grid: &Grid<T>,
+    palette: &impl Palette<T>,
+) -> io::Result<()> {
+    write_raster(writer, "P6", grid, |tile| {
+        let Rgb(r, g, b) = palette.color(tile);
+        vec![r, g, b]
+    })
+}
+
+pub fn write_pgm<T>(
+    writer: &mut impl Write,
+    grid: &Grid<T>,
+    palette: &impl Palette<T>,
+) -> io::Result<()> {
+    write_raster(writer, "P5", grid, |tile| {
+        vec![palette.color(tile).luminance()]
+    })
+}
+
+fn write_raster<T>(
+    writer: &mut impl Write,
+    magic: &str,
+    grid: &Grid<T>,
+    pixel: impl Fn(&T) -> Vec<u8>,
+) -> io::Result<()> {
+    let width = grid.width() as usize * CELL_SIZE;
+    let height = grid.height() as usize * CELL_SIZE;
+    write!(writer, "{magic}\n{width} {height}\n255\n")?;
+
+    let mut line = Vec::new();
+    for row in grid.rows() {
+        line.clear();
+        for tile in row.iter() {
+            let pixel = pixel(tile);
+            for _ in 0..CELL_SIZE {
+                line.extend_from_slice(&pixel);
+            }
+        }
+
+        for _ in 0..CELL_SIZE {
+            writer.write_all(&line)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_svg<T>(
+    writer: &mut impl Write,
+    grid: &Grid<T>,
+    palette: &impl Palette<T>,
+) -> io::Result<()> {
+    let width = grid.width();
+    let height = grid.height();
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" width="{}" height="{}" shape-rendering="crispEdges">"#,
+        width as usize * CELL_SIZE,
+        height as usize * CELL_SIZE,
+    )?;
+
+    for (y, row) in grid.rows().enumerate() {
+        // Merge horizontal runs of the same colour to keep the file small
+        let mut run: Option<(usize, usize, Rgb)> = None;
+        for (x, tile) in row.iter().enumerate() {
+            let color = palette.color(tile);
+            match run {
+                Some((_, ref mut len, run_color)) if run_color == color => *len += 1,
+                _ => {
+                    if let Some((start, len, color)) = run {
+                        write_svg_rect(writer, start, y, len, color)?;
+                    }
+
+                    run = Some((x, 1, color));
+                }
+            }
+        }
+
+        if let Some((start, len, color)) = run {
+            write_svg_rect(writer, start, y, len, color)?;
+        }
+    }
+
+    writeln!(writer, "</svg>")
+}
+
+fn write_svg_rect(
+    writer: &mut impl Write,
+    x: usize,
+    y: usize,
+    width: usize,
+    color: Rgb,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        r#"  <rect x="{x}" y="{y}" width="{width}" height="1" fill="{color}"/>"#
+    )
+}
+
+#[derive(Debug)]
+struct Renderer {
+    directory: PathBuf,
+    format: Format,
+}
+
+/// Enables rendering: every snapshot and frame will be written to `directory`.
+pub fn set_output(directory: PathBuf, format: Format) -> io::Result<()> {
+    std::fs::create_dir_all(&directory)?;
+    RENDERER
+        .set(Renderer { directory, format })
+        .map_err(|_| io::Error::other("Render output is already configured"))
+}
+
+pub fn is_enabled() -> bool {
+    RENDERER.get().is_some()
+}
+
+/// Writes a single image named `name` if rendering is enabled.
+pub fn snapshot<T>(name: &str, grid: &Grid<T>, palette: impl Palette<T>) {
+    let Some(renderer) = RENDERER.get() else {
+        return;
+    };
+
+    let file_name = format!("{name}.{}", renderer.format.extension());
+    let result = create(&file_name)
+        .expect("Rendering is enabled")
+        .and_then(|mut file| {
+            write(&mut file, renderer.format, grid, &palette)?;
+            file.flush()
+        });
+
+    if let Err(e) = result {
+        tracing::warn!(%e, file_name, "Unable to write render output");
+    }
+}
+
+/// Writes the `index`-th image of the `name` sequence if rendering is enabled.
+pub fn frame<T>(name: &str, index: usize, grid: &Grid<T>, palette: impl Palette<T>) {
+    if is_enabled() {
+        snapshot(&format!("{name}-{index:06}"), grid, palette);
+    }
+}
+
+/// Creates a raw file in the render directory, for outputs that are not grids.
+///
+/// Returns `None` if rendering is disabled.
+pub fn create(file_name: &str) -> Option<io::Result<BufWriter<File>>> {
+    let renderer = RENDERER.get()?;
+    let path = renderer.directory.join(file_name);
+    Some(File::create(path).map(BufWriter::new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Grid<bool> {
+        let mut grid = Grid::new(3, 2);
+        grid[aoc_utils::grid::Coordinates::new(0, 0)] = true;
+        grid[aoc_utils::grid::Coordinates::new(2, 1)] = true;
+        grid
+    }
+
+    fn palette(tile: &bool) -> Rgb {
+        if *tile {
+            Rgb::WHITE
+        } else {
+            Rgb::BLACK
+        }
+    }
+
+    #[test]
+    fn test_ppm() {
+        let mut output = Vec::new();
+        write_ppm(&mut output, &checkerboard(), &palette).unwrap();
+
+        let header = format!("P6\n{} {}\n255\n", 3 * CELL_SIZE, 2 * CELL_SIZE);
+        assert!(output.starts_with(header.as_bytes()));
+        assert_eq!(output.len(), header.len() + 6 * CELL_SIZE * CELL_SIZE * 3);
+        assert_eq!(&output[header.len()..header.len() + 3], &[255, 255, 255]);
+    }
+
+    #[test]
+    fn test_pgm() {
+        let mut output = Vec::new();
+        write_pgm(&mut output, &checkerboard(), &palette).unwrap();
+
+        let header = format!("P5\n{} {}\n255\n", 3 * CELL_SIZE, 2 * CELL_SIZE);
+        assert_eq!(output.len(), header.len() + 6 * CELL_SIZE * CELL_SIZE);
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut output = Vec::new();
+        write_svg(&mut output, &checkerboard(), &palette).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("<rect").count(), 4);
+        assert!(output.contains(r##"<rect x="1" y="0" width="2" height="1" fill="#000000"/>"##));
+    }
+}
@@ -0,0 +1,127 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use aoc_utils::{harness::Runner, problem::ProblemCollection};
+
+use crate::{boundary::Panic, cache::CacheStats, cancel, dump};
+
+/// Stack size of the threads running detached parts, matching the main thread's.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+pub type PartResult = Result<anyhow::Result<String>, Panic>;
+
+/// Runs a part inside a panic boundary, reading `input` and `params` if given, and writing its
+/// artifacts to `dump`.
+pub fn run_part(
+    runner: &dyn Runner,
+    input: Option<&str>,
+    params: BTreeMap<String, i64>,
+    dump: Option<dump::Sink>,
+) -> PartResult {
+    run_part_with_caches(runner, input, params, dump).0
+}
+
+/// Runs a part like [`run_part`], also returning the usage of the caches it filled.
+pub fn run_part_with_caches(
+    runner: &dyn Runner,
+    input: Option<&str>,
+    params: BTreeMap<String, i64>,
+    dump: Option<dump::Sink>,
+) -> (PartResult, Vec<CacheStats>) {
+    // Drop whatever earlier parts left behind
+    crate::cache::take_stats();
+    crate::parsed::clear_parse_time();
+
+    let result = dump::with_sink(dump, || {
+        crate::input::with_params(params, || match input {
+            Some(input) => {
+                crate::input::with_override(input, || crate::boundary::catch(|| runner.run()))
+            }
+            None => crate::boundary::catch(|| runner.run()),
+        })
+    });
+
+    let caches = crate::cache::take_stats();
+    for stats in caches.iter() {
+        tracing::debug!(
+            name = stats.name,
+            instances = stats.instances,
+            hits = stats.hits,
+            misses = stats.misses,
+            inserts = stats.inserts,
+            size = stats.size,
+            hit_rate = stats.hit_rate(),
+            "Cache"
+        );
+    }
+
+    (result, caches)
+}
+
+/// Runs `f` on a thread of its own, giving up on it after `timeout`.
+///
+/// The thread is cancelled when giving up on it, so it stops at the next [`cancel::check`].
+/// Runners borrow from their collection, so `f` should build its own in case the thread
+/// outlives the caller.
+pub fn run_detached<T>(
+    name: String,
+    timeout: Duration,
+    f: impl FnOnce() -> T + Send + 'static,
+) -> std::io::Result<Option<T>>
+where
+    T: Send + 'static,
+{
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let token = cancel::Token::new();
+
+    std::thread::Builder::new()
+        .name(name)
+        .stack_size(STACK_SIZE)
+        .spawn({
+            let token = token.clone();
+            move || {
+                // The receiver is gone if the part timed out
+                let _ = sender.send(cancel::with_token(token, f));
+            }
+        })?;
+
+    let result = receiver.recv_timeout(timeout).ok();
+    if result.is_none() {
+        token.cancel();
+    }
+
+    Ok(result)
+}
+
+/// Finds part `part_idx` (starting at 0) of a problem.
+pub fn find_part(
+    problems: &ProblemCollection,
+    problem_num: i32,
+    part_idx: usize,
+) -> Option<&dyn Runner> {
+    problems
+        .get(problem_num)?
+        .iter()
+        .find(|&(idx, _)| idx == part_idx)
+        .map(|(_, runner)| runner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_detached_cancels() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let result = run_detached("test".to_owned(), Duration::from_millis(10), move || {
+            let result = crate::boundary::catch(|| loop {
+                cancel::check();
+                std::thread::yield_now();
+            });
+            sender.send(result).unwrap();
+        });
+
+        assert_eq!(result.unwrap(), None);
+        let result = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(result.unwrap_err().message, "Cancelled");
+    }
+}
@@ -0,0 +1,483 @@
+use std::{
+    collections::{BinaryHeap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use aoc_utils::hashbrown::{hash_map::Entry, HashMap, HashSet};
+
+/// Breadth-first search from `start`, where every edge has a cost of 1.
+pub fn bfs<N, F, I>(start: N, successors: F) -> ResultSet<N, u64>
+where
+    N: Copy + Eq + Hash,
+    F: FnMut(N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let (_, results) = breadth_first(start, successors, |_| false);
+    results
+}
+
+/// Breadth-first search from `start`, stopping once a node satisfying `is_goal` is reached.
+pub fn bfs_until<N, F, I, G>(start: N, successors: F, is_goal: G) -> Option<(N, ResultSet<N, u64>)>
+where
+    N: Copy + Eq + Hash,
+    F: FnMut(N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(N) -> bool,
+{
+    let (goal, results) = breadth_first(start, successors, is_goal);
+    goal.map(|goal| (goal, results))
+}
+
+fn breadth_first<N, F, I, G>(
+    start: N,
+    mut successors: F,
+    mut is_goal: G,
+) -> (Option<N>, ResultSet<N, u64>)
+where
+    N: Copy + Eq + Hash,
+    F: FnMut(N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(N) -> bool,
+{
+    let mut results = ResultSet::new(start);
+    let mut queue = VecDeque::new();
+    queue.push_back((start, 0));
+    let mut cancel = crate::cancel::Periodic::new();
+
+    while let Some((node, cost)) = queue.pop_front() {
+        cancel.check();
+
+        if is_goal(node) {
+            return (Some(node), results);
+        }
+
+        for next in successors(node) {
+            if results.visit(node, next, cost + 1) {
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+
+    (None, results)
+}
+
+/// Dijkstra's algorithm from `start`, keeping every predecessor that lies on a cheapest path.
+pub fn dijkstra<N, C, F, I>(start: N, mut successors: F) -> ResultSet<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut results = ResultSet::new(start);
+    let mut queue = BinaryHeap::new();
+    queue.push(Candidate::new(start, C::default(), C::default()));
+    let mut cancel = crate::cancel::Periodic::new();
+
+    while let Some(Candidate { node, cost, .. }) = queue.pop() {
+        cancel.check();
+
+        if results.cost(node).is_some_and(|best| cost > best) {
+            continue;
+        }
+
+        for (next, edge_cost) in successors(node) {
+            let next_cost = cost + edge_cost;
+            if results.visit(node, next, next_cost) {
+                queue.push(Candidate::new(next, next_cost, next_cost));
+            }
+        }
+    }
+
+    results
+}
+
+/// A* search from `start` towards the first node satisfying `is_goal`.
+///
+/// Keeps searching until every candidate that could still tie with the goal has been expanded,
+/// so the goal's predecessor set is complete as long as the heuristic is consistent.
+pub fn astar<N, C, F, I, H, G>(
+    start: N,
+    mut successors: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<(N, ResultSet<N, C>)>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(N) -> C,
+    G: FnMut(N) -> bool,
+{
+    let mut results = ResultSet::new(start);
+    let mut queue = BinaryHeap::new();
+    queue.push(Candidate::new(start, C::default(), heuristic(start)));
+    let mut cancel = crate::cancel::Periodic::new();
+    let mut found: Option<(N, C)> = None;
+
+    while let Some(Candidate {
+        node,
+        cost,
+        priority,
+    }) = queue.pop()
+    {
+        cancel.check();
+
+        if found.is_some_and(|(_, goal_cost)| priority > goal_cost) {
+            break;
+        }
+
+        if results.cost(node).is_some_and(|best| cost > best) {
+            continue;
+        }
+
+        if is_goal(node) {
+            found.get_or_insert((node, cost));
+            continue;
+        }
+
+        for (next, edge_cost) in successors(node) {
+            let next_cost = cost + edge_cost;
+            if results.visit(node, next, next_cost) {
+                let priority = next_cost + heuristic(next);
+                queue.push(Candidate::new(next, next_cost, priority));
+            }
+        }
+    }
+
+    found.map(|(goal, _)| (goal, results))
+}
+
+struct Candidate<N, C> {
+    node: N,
+    cost: C,
+    priority: C,
+}
+
+impl<N, C> Candidate<N, C> {
+    fn new(node: N, cost: C, priority: C) -> Self {
+        Self {
+            node,
+            cost,
+            priority,
+        }
+    }
+}
+
+impl<N, C: Ord> PartialEq for Candidate<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority.eq(&other.priority)
+    }
+}
+
+impl<N, C: Ord> Eq for Candidate<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Candidate<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Candidate<N, C> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Intentionally a reverse comparison
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Costs and cheapest-path predecessors of every node reached by a search.
+#[derive(Debug, Clone)]
+pub struct ResultSet<N, C> {
+    start: N,
+    store: HashMap<N, SearchEntry<N, C>>,
+}
+
+#[derive(Debug, Clone)]
+struct SearchEntry<N, C> {
+    cost: C,
+    predecessors: Vec<N>,
+}
+
+impl<N, C> ResultSet<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Default,
+{
+    fn new(start: N) -> Self {
+        let mut store = HashMap::new();
+        store.insert(
+            start,
+            SearchEntry {
+                cost: C::default(),
+                predecessors: Vec::new(),
+            },
+        );
+
+        Self { start, store }
+    }
+
+    /// Records an edge into `destination`, returning `true` if it improved the known cost.
+    ///
+    /// Edges back into the start are ignored, its cost can't improve and a predecessor would
+    /// make the start look like it's in the middle of a path.
+    fn visit(&mut self, source: N, destination: N, cost: C) -> bool {
+        if destination == self.start {
+            return false;
+        }
+
+        match self.store.entry(destination) {
+            Entry::Vacant(vacant) => {
+                vacant.insert(SearchEntry {
+                    cost,
+                    predecessors: vec![source],
+                });
+                true
+            }
+            Entry::Occupied(mut occupied) => {
+                let entry = occupied.get_mut();
+                if cost < entry.cost {
+                    entry.cost = cost;
+                    entry.predecessors.clear();
+                    entry.predecessors.push(source);
+                    true
+                } else {
+                    if cost == entry.cost && !entry.predecessors.contains(&source) {
+                        entry.predecessors.push(source);
+                    }
+
+                    false
+                }
+            }
+        }
+    }
+
+    pub fn start(&self) -> N {
+        self.start
+    }
+
+    pub fn cost(&self, node: N) -> Option<C> {
+        self.store.get(&node).map(|entry| entry.cost)
+    }
+
+    pub fn predecessors(&self, node: N) -> &[N] {
+        self.store
+            .get(&node)
+            .map(|entry| entry.predecessors.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (N, C)> + use<'_, N, C> {
+        self.store.iter().map(|(&node, entry)| (node, entry.cost))
+    }
+
+    /// One cheapest path from the start to `node`, both ends included.
+    ///
+    /// Zero-cost cycles can make nodes each other's predecessors, so nodes already on the path
+    /// are skipped.
+    pub fn path(&self, node: N) -> Option<Vec<N>> {
+        self.store.get(&node)?;
+
+        let mut path = vec![node];
+        let mut visited: HashSet<N> = HashSet::from_iter([node]);
+        let mut current = node;
+        while let Some(&previous) = self
+            .predecessors(current)
+            .iter()
+            .find(|previous| !visited.contains(*previous))
+        {
+            visited.insert(previous);
+            path.push(previous);
+            current = previous;
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every edge that lies on at least one cheapest path from the start to `node`.
+    pub fn edges_on_paths(&self, node: N) -> HashSet<(N, N)> {
+        let mut edges = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![node];
+
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node) {
+                continue;
+            }
+
+            for &previous in self.predecessors(node) {
+                edges.insert((previous, node));
+                stack.push(previous);
+            }
+        }
+
+        edges
+    }
+
+    /// Every node that lies on at least one cheapest path from the start to `node`.
+    pub fn nodes_on_paths(&self, node: N) -> HashSet<N> {
+        let mut nodes = HashSet::new();
+        if !self.store.contains_key(&node) {
+            return nodes;
+        }
+
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            if nodes.insert(node) {
+                stack.extend(self.predecessors(node).iter().copied());
+            }
+        }
+
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_neighbors(
+        size: i32,
+        walls: &[(i32, i32)],
+    ) -> impl FnMut((i32, i32)) -> Vec<(i32, i32)> + '_ {
+        move |(x, y)| {
+            [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| (0..size).contains(&x) && (0..size).contains(&y))
+                .filter(|node| !walls.contains(node))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let walls = [(1, 0), (1, 1), (1, 2)];
+        let results = bfs((0, 0), grid_neighbors(4, &walls));
+
+        assert_eq!(results.cost((2, 0)), Some(8));
+        assert_eq!(results.cost((1, 0)), None);
+        assert_eq!(results.path((0, 3)).unwrap().len(), 4);
+        assert_eq!(results.len(), 13);
+    }
+
+    #[test]
+    fn test_bfs_until() {
+        let walls = [(1, 0), (1, 1), (1, 2)];
+
+        let (goal, results) =
+            bfs_until((0, 0), grid_neighbors(4, &walls), |node| node == (0, 2)).unwrap();
+        assert_eq!(goal, (0, 2));
+        assert_eq!(results.cost(goal), Some(2));
+        assert!(results.len() < 13);
+
+        assert!(bfs_until((0, 0), grid_neighbors(4, &walls), |node| node == (1, 1)).is_none());
+    }
+
+    #[test]
+    fn test_dijkstra_keeps_all_cheapest_predecessors() {
+        let edges = [
+            ('a', 'b', 1),
+            ('a', 'c', 1),
+            ('b', 'd', 1),
+            ('c', 'd', 1),
+            ('a', 'd', 3),
+        ];
+
+        let results = dijkstra('a', |node| {
+            edges
+                .iter()
+                .filter(move |edge| edge.0 == node)
+                .map(|edge| (edge.1, edge.2))
+        });
+
+        assert_eq!(results.cost('d'), Some(2));
+        let mut predecessors = results.predecessors('d').to_vec();
+        predecessors.sort();
+        assert_eq!(predecessors, ['b', 'c']);
+        assert_eq!(results.edges_on_paths('d').len(), 4);
+        assert_eq!(results.nodes_on_paths('d').len(), 4);
+    }
+
+    #[test]
+    fn test_astar() {
+        let walls = [(1, 1), (2, 1), (3, 1), (1, 3), (2, 3), (3, 3)];
+        let mut neighbors = grid_neighbors(5, &walls);
+        let target = (4, 4);
+
+        let (goal, results) = astar(
+            (0, 0),
+            |node| neighbors(node).into_iter().map(|next| (next, 1u64)),
+            |(x, y)| (x.abs_diff(target.0) + y.abs_diff(target.1)) as u64,
+            |node| node == target,
+        )
+        .unwrap();
+
+        assert_eq!(goal, target);
+        assert_eq!(results.cost(target), Some(8));
+        assert_eq!(results.path(target).unwrap().first(), Some(&(0, 0)));
+    }
+
+    #[test]
+    fn test_astar_keeps_tied_predecessors() {
+        // `c` reaches the goal for free, so it ties with the goal and has to be expanded too
+        let edges = [
+            ('s', 'a', 1),
+            ('a', 'g', 1),
+            ('s', 'b', 1),
+            ('b', 'c', 1),
+            ('c', 'g', 0),
+        ];
+
+        let (goal, results) = astar(
+            's',
+            |node| {
+                edges
+                    .iter()
+                    .filter(move |edge| edge.0 == node)
+                    .map(|edge| (edge.1, edge.2))
+            },
+            |_| 0,
+            |node| node == 'g',
+        )
+        .unwrap();
+
+        assert_eq!(goal, 'g');
+        assert_eq!(results.cost('g'), Some(2));
+        let mut predecessors = results.predecessors('g').to_vec();
+        predecessors.sort();
+        assert_eq!(predecessors, ['a', 'c']);
+        assert_eq!(results.nodes_on_paths('g').len(), 5);
+    }
+
+    #[test]
+    fn test_zero_cost_cycles() {
+        let edges = [
+            ('a', 'b', 0),
+            ('b', 'a', 0),
+            ('b', 'c', 0),
+            ('c', 'b', 0),
+            ('c', 'd', 1),
+        ];
+
+        let results = dijkstra('a', |node| {
+            edges
+                .iter()
+                .filter(move |edge| edge.0 == node)
+                .map(|edge| (edge.1, edge.2))
+        });
+
+        assert!(results.predecessors('a').is_empty());
+        assert_eq!(results.path('d'), Some(vec!['a', 'b', 'c', 'd']));
+        assert_eq!(results.nodes_on_paths('d').len(), 4);
+    }
+}
@@ -0,0 +1,390 @@
+use std::{
+    collections::BTreeMap,
+    io::Read,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use aoc_utils::problem::ProblemCollection;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    cache::CacheStats,
+    config::Config,
+    run::{find_part, run_detached, run_part_with_caches},
+};
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub port: u16,
+    /// Largest request body accepted, in bytes
+    pub max_body: usize,
+    /// Time after which a part is abandoned
+    pub timeout: Duration,
+    /// Parameters used unless the request overrides them
+    pub config: Config,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SolveRequest {
+    input: String,
+    #[serde(default)]
+    params: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct SolveResponse {
+    day: i32,
+    part: usize,
+    answer: Option<String>,
+    elapsed_ms: f64,
+    error: Option<String>,
+    /// Usage of the caches the part filled
+    caches: Vec<CacheStats>,
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    body: serde_json::Value,
+}
+
+impl Response {
+    fn error(status: u16, message: impl std::fmt::Display) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.to_string() }),
+        }
+    }
+}
+
+/// Number of parts still running on their own thread, including those abandoned after timing
+/// out until they notice they were cancelled.
+#[derive(Debug, Default, Clone)]
+struct Running(Arc<AtomicUsize>);
+
+impl Running {
+    /// Counts one more part until the returned guard is dropped.
+    fn start(&self) -> RunningGuard {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        RunningGuard(self.0.clone())
+    }
+
+    fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+struct RunningGuard(Arc<AtomicUsize>);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Serves the problems built by `registry` on localhost until the process is stopped:
+///
+/// - `GET /days` lists the days and their parts
+/// - `POST /days/{day}/parts/{part}` solves a part, with a body like
+///   `{"input": "...", "params": {"width": 11}}`
+///
+/// Requests are handled one at a time, each part running on a thread of its own so that it can
+/// be cancelled after the timeout. Parts only stop at their next cancellation check, so solving
+/// is refused with a 503 until an abandoned part has actually stopped.
+pub fn serve(
+    registry: impl Fn() -> ProblemCollection + Clone + Send + 'static,
+    options: &Options,
+) -> anyhow::Result<()> {
+    let server = tiny_http::Server::http(("127.0.0.1", options.port))
+        .map_err(|e| anyhow::anyhow!("Unable to listen on port {}: {e}", options.port))?;
+    tracing::info!(port = options.port, "Serving");
+
+    let problems = registry();
+    let running = Running::default();
+    for mut request in server.incoming_requests() {
+        let response = match read_body(&mut request, options.max_body) {
+            Ok(body) => handle(
+                &problems,
+                &registry,
+                &running,
+                options,
+                request.method(),
+                request.url(),
+                &body,
+            ),
+            Err(response) => response,
+        };
+
+        let header = tiny_http::Header::from_bytes("Content-Type", "application/json")
+            .expect("Header should be valid");
+        let reply = tiny_http::Response::from_string(response.body.to_string())
+            .with_status_code(response.status)
+            .with_header(header);
+
+        if let Err(e) = request.respond(reply) {
+            tracing::warn!(%e, "Unable to respond");
+        }
+    }
+
+    Ok(())
+}
+
+fn read_body(request: &mut tiny_http::Request, max_body: usize) -> Result<Vec<u8>, Response> {
+    let too_large = || Response::error(413, format!("Body is larger than {max_body} bytes"));
+
+    if request
+        .body_length()
+        .is_some_and(|length| length > max_body)
+    {
+        return Err(too_large());
+    }
+
+    // The length may be missing, so don't trust it either way
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(max_body as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| Response::error(400, e))?;
+
+    if body.len() > max_body {
+        return Err(too_large());
+    }
+
+    Ok(body)
+}
+
+fn handle(
+    problems: &ProblemCollection,
+    registry: &(impl Fn() -> ProblemCollection + Clone + Send + 'static),
+    running: &Running,
+    options: &Options,
+    method: &tiny_http::Method,
+    url: &str,
+    body: &[u8],
+) -> Response {
+    let path = url.split('?').next().unwrap_or_default();
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    match (method, segments.as_slice()) {
+        (tiny_http::Method::Get, ["days"]) => list(problems),
+        (tiny_http::Method::Post, ["days", day, "parts", part]) => {
+            let (Ok(day), Ok(part)) = (day.parse::<i32>(), part.parse::<usize>()) else {
+                return Response::error(404, "Not found");
+            };
+
+            solve(problems, registry, running, options, day, part, body)
+        }
+        (_, ["days"] | ["days", _, "parts", _]) => Response::error(405, "Method not allowed"),
+        _ => Response::error(404, "Not found"),
+    }
+}
+
+fn list(problems: &ProblemCollection) -> Response {
+    let days = problems
+        .iter()
+        .map(|(day, problem)| {
+            let parts = problem.iter().map(|(idx, _)| idx + 1).collect::<Vec<_>>();
+            json!({ "day": day, "parts": parts })
+        })
+        .collect::<Vec<_>>();
+
+    Response {
+        status: 200,
+        body: json!({ "days": days }),
+    }
+}
+
+fn solve(
+    problems: &ProblemCollection,
+    registry: &(impl Fn() -> ProblemCollection + Clone + Send + 'static),
+    running: &Running,
+    options: &Options,
+    day: i32,
+    part: usize,
+    body: &[u8],
+) -> Response {
+    let exists = problems
+        .get(day)
+        .is_some_and(|problem| problem.iter().any(|(idx, _)| idx + 1 == part));
+    if !exists {
+        return Response::error(404, format!("No part {part} for day {day}"));
+    }
+
+    let request = match serde_json::from_slice::<SolveRequest>(body) {
+        Ok(request) => request,
+        Err(e) => return Response::error(400, e),
+    };
+
+    // Requests are handled one at a time, so anything still running was abandoned
+    if running.count() > 0 {
+        return Response::error(
+            503,
+            "A part that timed out is still running, try again later",
+        );
+    }
+
+    let mut params = options.config.params(day);
+    params.extend(request.params);
+
+    let guard = running.start();
+    let registry = registry.clone();
+    let input = request.input;
+    let start = Instant::now();
+    let result = run_detached(
+        format!("problem-{day}-{part}"),
+        options.timeout,
+        move || {
+            let _guard = guard;
+            let problems = registry();
+            let runner = find_part(&problems, day, part - 1).expect("Part should exist");
+            run_part_with_caches(runner, Some(&input), params, None)
+        },
+    );
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let (result, caches) = match result {
+        Ok(Some((result, caches))) => (Ok(Some(result)), caches),
+        Ok(None) => (Ok(None), Vec::new()),
+        Err(e) => (Err(e), Vec::new()),
+    };
+
+    let (status, answer, error) = match result {
+        Ok(Some(Ok(Ok(answer)))) => (200, Some(answer), None),
+        Ok(Some(Ok(Err(e)))) => (422, None, Some(e.to_string())),
+        Ok(Some(Err(panic))) => (422, None, Some(panic.to_string())),
+        Ok(None) => (
+            504,
+            None,
+            Some(format!("Timed out after {:?}", options.timeout)),
+        ),
+        Err(e) => (500, None, Some(e.to_string())),
+    };
+
+    let response = SolveResponse {
+        day,
+        part,
+        answer,
+        elapsed_ms,
+        error,
+        caches,
+    };
+
+    Response {
+        status,
+        body: serde_json::to_value(response).expect("Response should serialize"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::harness::SimpleHarness;
+
+    use super::*;
+
+    fn registry() -> ProblemCollection {
+        ProblemCollection::builder(SimpleHarness)
+            .add_problem(1, crate::day01::problem)
+            .build()
+    }
+
+    fn request(method: tiny_http::Method, url: &str, body: &str) -> Response {
+        request_while(&Running::default(), method, url, body)
+    }
+
+    fn request_while(
+        running: &Running,
+        method: tiny_http::Method,
+        url: &str,
+        body: &str,
+    ) -> Response {
+        let options = Options {
+            port: 0,
+            max_body: 1024,
+            timeout: Duration::from_secs(10),
+            config: Config::default(),
+        };
+
+        handle(
+            &registry(),
+            &registry,
+            running,
+            &options,
+            &method,
+            url,
+            body.as_bytes(),
+        )
+    }
+
+    #[test]
+    fn test_list() {
+        let response = request(tiny_http::Method::Get, "/days", "");
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            response.body,
+            json!({ "days": [{ "day": 1, "parts": [1, 2] }] })
+        );
+    }
+
+    #[test]
+    fn test_solve() {
+        let body = r#"{"input": "3   4\n4   3\n"}"#;
+        let response = request(tiny_http::Method::Post, "/days/1/parts/2", body);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body["answer"], "7");
+        assert!(response.body["error"].is_null());
+    }
+
+    #[test]
+    fn test_solve_error() {
+        let body = r#"{"input": "three   4\n"}"#;
+        let response = request(tiny_http::Method::Post, "/days/1/parts/1", body);
+        assert_eq!(response.status, 422);
+        assert!(response.body["answer"].is_null());
+        assert!(response.body["error"].is_string());
+    }
+
+    #[test]
+    fn test_solve_while_abandoned_part_runs() {
+        let running = Running::default();
+        let body = r#"{"input": "3   4\n4   3\n"}"#;
+
+        let guard = running.start();
+        let response = request_while(&running, tiny_http::Method::Post, "/days/1/parts/2", body);
+        assert_eq!(response.status, 503);
+
+        drop(guard);
+        let response = request_while(&running, tiny_http::Method::Post, "/days/1/parts/2", body);
+        assert_eq!(response.status, 200);
+        assert_eq!(running.count(), 0);
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let status = |method, url, body| request(method, url, body).status;
+
+        assert_eq!(
+            status(tiny_http::Method::Post, "/days/2/parts/1", "{}"),
+            404
+        );
+        assert_eq!(
+            status(tiny_http::Method::Post, "/days/1/parts/3", "{}"),
+            404
+        );
+        assert_eq!(
+            status(tiny_http::Method::Post, "/days/1/parts/1", "{}"),
+            400
+        );
+        assert_eq!(status(tiny_http::Method::Get, "/days/1/parts/1", ""), 405);
+        assert_eq!(status(tiny_http::Method::Get, "/unknown", ""), 404);
+    }
+}
@@ -1,23 +1,43 @@
 use aoc_utils::AocError;
 
+use crate::{generate::Rng, parsed::ParseStage};
+
 const INPUT: &str = include_str!("input.txt");
 
 pub fn problem<H>(builder: &mut aoc_utils::problem::ProblemBuilder<H>)
 where
     H: aoc_utils::harness::Harness,
 {
-    builder.add_part(|| part_1(INPUT));
-    builder.add_part(|| part_2(INPUT));
+    builder
+        .parse_input(INPUT, parse)
+        .add_part(part_1)
+        .add_part(part_2);
 }
 
-fn part_1(_input: &str) -> anyhow::Result<i64> {
+fn part_1(_input: &Input) -> anyhow::Result<i64> {
     Err(AocError::Todo.into())
 }
 
-fn part_2(_input: &str) -> anyhow::Result<i64> {
+fn part_2(_input: &Input) -> anyhow::Result<i64> {
+    Err(AocError::Todo.into())
+}
+
+/// The input, parsed once for both parts.
+struct Input {}
+
+fn parse(_input: &str) -> anyhow::Result<Input> {
     Err(AocError::Todo.into())
 }
 
+#[cfg(feature = "fuzzing")]
+pub fn fuzz(input: &str) {
+    let _ = parse(input);
+}
+
+pub fn generate(_rng: &mut Rng, _size: usize) -> String {
+    String::new()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -26,11 +46,11 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let _result = part_1(EXAMPLE_1).unwrap();
+        let _result = part_1(&parse(EXAMPLE_1).unwrap()).unwrap();
     }
 
     #[test]
     fn test_part_2() {
-        let _result = part_2(EXAMPLE_1).unwrap();
+        let _result = part_2(&parse(EXAMPLE_1).unwrap()).unwrap();
     }
 }
@@ -0,0 +1,149 @@
+use std::{collections::BTreeMap, fmt::Display, time::Instant};
+
+use aoc_utils::harness::{Harness, Runner};
+
+/// Variants by day and part, in the order they were added.
+type Variants = BTreeMap<(i32, usize), Vec<(&'static str, Box<dyn Runner + Send + Sync>)>>;
+
+/// Named alternative solutions to parts, e.g. `14:2@crt`, kept next to the ones registered in
+/// each day's `problem`.
+pub struct VariantCollection {
+    variants: Variants,
+}
+
+impl VariantCollection {
+    pub fn builder<H>(harness: H) -> VariantCollectionBuilder<H>
+    where
+        H: Harness,
+    {
+        VariantCollectionBuilder {
+            harness,
+            variants: BTreeMap::new(),
+        }
+    }
+
+    /// Days and parts (starting at 1) with variants.
+    pub fn parts(&self) -> impl Iterator<Item = (i32, usize)> + '_ {
+        self.variants.keys().copied()
+    }
+
+    /// Variants of `part` (starting at 1) of `day`, in the order they were added.
+    pub fn get(&self, day: i32, part: usize) -> impl Iterator<Item = (&'static str, &dyn Runner)> {
+        self.variants
+            .get(&(day, part))
+            .into_iter()
+            .flatten()
+            .map(|(name, runner)| (*name, runner.as_ref() as &dyn Runner))
+    }
+
+    pub fn find(&self, day: i32, part: usize, name: &str) -> Option<&dyn Runner> {
+        self.get(day, part)
+            .find(|&(variant, _)| variant == name)
+            .map(|(_, runner)| runner)
+    }
+}
+
+pub struct VariantCollectionBuilder<H> {
+    harness: H,
+    variants: Variants,
+}
+
+impl<H> VariantCollectionBuilder<H>
+where
+    H: Harness,
+{
+    pub fn add_day<F>(mut self, day: i32, f: F) -> Self
+    where
+        F: FnOnce(&mut VariantBuilder),
+    {
+        let mut builder = VariantBuilder {
+            harness: &self.harness,
+            variants: Vec::new(),
+        };
+        f(&mut builder);
+
+        for (part, name, runner) in builder.variants {
+            self.variants
+                .entry((day, part))
+                .or_default()
+                .push((name, runner));
+        }
+
+        self
+    }
+
+    pub fn build(self) -> VariantCollection {
+        VariantCollection {
+            variants: self.variants,
+        }
+    }
+}
+
+pub struct VariantBuilder<'a> {
+    harness: &'a dyn Harness,
+    variants: Vec<(usize, &'static str, Box<dyn Runner + Send + Sync>)>,
+}
+
+impl VariantBuilder<'_> {
+    /// Adds `f` as the variant `name` of `part` (starting at 1).
+    pub fn add_variant<F, T>(&mut self, part: usize, name: &'static str, f: F) -> &mut Self
+    where
+        F: Fn() -> anyhow::Result<T> + Send + Sync + 'static,
+        T: Display,
+    {
+        let runner = VariantRunner {
+            f,
+            harness: self.harness.boxed(),
+        };
+        self.variants.push((part, name, Box::new(runner)));
+        self
+    }
+}
+
+struct VariantRunner<F> {
+    f: F,
+    harness: Box<dyn Harness + Send + Sync>,
+}
+
+impl<F, T> Runner for VariantRunner<F>
+where
+    F: Fn() -> anyhow::Result<T>,
+    T: Display,
+{
+    fn run(&self) -> anyhow::Result<String> {
+        let start = Instant::now();
+        let output = (self.f)()?;
+        Ok(self.harness.wrap(start.elapsed(), output.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::harness::SimpleHarness;
+
+    use super::*;
+
+    #[test]
+    fn test_variants() {
+        let variants = VariantCollection::builder(SimpleHarness)
+            .add_day(1, |day| {
+                day.add_variant(2, "fast", || Ok(1));
+                day.add_variant(2, "slow", || Ok(2));
+            })
+            .add_day(3, |day| {
+                day.add_variant(1, "only", || Ok("three"));
+            })
+            .build();
+
+        assert_eq!(variants.parts().collect::<Vec<_>>(), vec![(1, 2), (3, 1)]);
+
+        let names = variants.get(1, 2).map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names, vec!["fast", "slow"]);
+        assert_eq!(variants.get(1, 1).count(), 0);
+
+        let run = |day, part, name| variants.find(day, part, name).map(|r| r.run().unwrap());
+        assert_eq!(run(1, 2, "slow"), Some("2".to_owned()));
+        assert_eq!(run(3, 1, "only"), Some("three".to_owned()));
+        assert_eq!(run(3, 1, "fast"), None);
+    }
+}